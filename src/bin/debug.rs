@@ -1,18 +1,16 @@
 use vgc_data::*;
 use vgc_data::read::VirtualFile;
 
-fn check_garc(garc: &garc::GARC) -> Result<(), std::io::Error> {
+fn check_garc(garc: &garc::GARC, filename: &str) -> Result<(), std::io::Error> {
     let mut it = garc.entries();
     while let Some(entry) = it.try_next()? {
         let mut jt = entry.entries();
         while let Some(subentry) = jt.try_next()? {
-
-            //println!("  {}.{}", entry.index(), subentry.index());
-            if let Ok(table) = pokemon::table::Table::new(subentry.reader()) {
-                println!("    BL: {} entries", table.len());
+            if let Ok(table) = subentry.reader().and_then(pokemon::table::Table::new) {
+                println!("  {}.{}.{:02} {}: {} entries", filename, entry.index(), subentry.index(), String::from_utf8_lossy(&table.magic()), table.len());
 
                 for subsubentry in table.entries() {
-                    println!("      {}", subsubentry.length());
+                    println!("      {}", subsubentry?.length());
                 }
             }
         }
@@ -21,28 +19,9 @@ fn check_garc(garc: &garc::GARC) -> Result<(), std::io::Error> {
     Ok(())
 }
 
-fn walkdir(mut entries: romfs::NodeIterator, filename: &str) -> Result<(), std::io::Error> {
-    while let Some(entry) = entries.next()? {
-        match entry {
-            romfs::Node::File(file) => {
-                if let Ok(garc) = garc::GARC::new(file.reader()) {
-                    //println!("garc: {}/{}", filename, file.basename());
-                    check_garc(&garc)?;
-                }
-            },
-            romfs::Node::Directory(dir) => {
-                let filename = format!("{}/{}", filename, dir.basename());
-                walkdir(dir.entries(), &filename)?;
-            },
-        }
-    }
-
-    Ok(())
-}
-
 fn main() -> Result<(), std::io::Error> {
     let filename = std::env::args().nth(1).unwrap();
-    let file = read::FileHolder::open(&filename)?;
+    let file = read::FileHolder::map(&filename)?;
     let ncsd = ncsd::NCSD::new(file.reader())?;
     let romfs = ncsd.partition(ncsd::Partition::Main)?.romfs()?.unwrap();
 
@@ -50,13 +29,18 @@ fn main() -> Result<(), std::io::Error> {
     let language = games::pokemon::Language::English;
     let species_names = game.species_names(language)?.entries().filter_map(Result::ok).collect::<Vec<_>>();
 
-    //walkdir(romfs.entries(), "")?;
+    for entry in romfs.walk()? {
+        if let (path, romfs::Node::File(file)) = entry? {
+            if let Ok(garc) = file.reader().and_then(garc::GARC::new) {
+                check_garc(&garc, &path)?;
+            }
+        }
+    }
 
     let file = romfs.file_at("a/1/5/6")?.unwrap();
     let file = if let romfs::Node::File(f) = file { f } else { panic!(""); };
-    let garc = garc::GARC::new(file.reader())?;
-    let table = pokemon::table::Table::new(garc.file_at(1, 0)?.unwrap().reader())?;
-    let entries = table.entries().collect::<Vec<_>>();
+    let garc = garc::GARC::new(file.reader()?)?;
+    let table = pokemon::table::Table::new(garc.file_at(1, 0)?.unwrap().reader()?)?;
 
     println!("tables: {}", table.len());
 
@@ -68,9 +52,9 @@ fn main() -> Result<(), std::io::Error> {
     // table 5: species ula'ula dex number
     // table 6: species poni dex number
 
-    println!("table size: {}", entries[7].length());
+    println!("table size: {}", table.entry(7)?.length());
 
-    //let values = raw_table_u16(entries[4].clone());
+    //let values = table.u8s(4)?;
 
     //for (i, v) in values.iter().enumerate() {
     //    println!("{} {}", v, species_names[i]);
@@ -79,35 +63,3 @@ fn main() -> Result<(), std::io::Error> {
 
     Ok(())
 }
-
-use std::io::Read;
-fn raw_table_u8(mut file: read::Reader) -> Vec<u8> {
-    let mut values = vec![];
-
-    loop {
-        let mut v = [0u8; 1];
-        match file.read_exact(&mut v) {
-            Ok(_) => { values.push(v[0]); },
-            Err(_) => { break; }
-        }
-    }
-
-    values
-}
-
-use byteorder::LittleEndian;
-use byteorder::ReadBytesExt;
-fn raw_table_u16(mut file: read::Reader) -> Vec<u16> {
-    let mut values = vec![];
-
-    loop {
-        match file.read_u16::<LittleEndian>() {
-            Ok(v) => { values.push(v); },
-            Err(_) => { break; }
-        }
-    }
-
-    values
-}
-
-
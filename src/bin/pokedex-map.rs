@@ -1,5 +1,3 @@
-use byteorder::LittleEndian;
-use byteorder::ReadBytesExt;
 use vgc_data::*;
 use clap::Clap;
 
@@ -11,20 +9,6 @@ struct Opts {
     lang: String,
 }
 
-
-fn raw_table(mut file: read::Reader) -> Vec<u16> {
-    let mut values = vec![];
-
-    loop {
-        match file.read_u16::<LittleEndian>() {
-            Ok(v) => { values.push(v); },
-            Err(_) => { break; }
-        }
-    }
-
-    values
-}
-
 fn main() -> Result<(), std::io::Error> {
     let opts: Opts = Opts::parse();
 
@@ -51,8 +35,7 @@ fn main() -> Result<(), std::io::Error> {
     let dex_entries = if opts.alt { game.alt_pokedex_entries(language) } else { game.pokedex_entries(language) }?.entries().filter_map(Result::ok).collect::<Vec<_>>();
 
     let pokedex_tables = game.form_linked_list()?;
-    let forms = pokedex_tables.entries().nth(0).unwrap();
-    let forms = raw_table(forms);
+    let forms = pokedex_tables.u16s(0)?;
 
     for i in 0..species_names.len() {
         let mut form = i;
@@ -23,9 +23,8 @@ pub fn main() -> Result<(), std::io::Error> {
         game.pokedex_entries(games::pokemon::Language::English)?
     };
 
-    let mut it = names.entries();
-    while let Some(text) = it.next()? {
-        println!("{:?}", text);
+    for text in names.entries() {
+        println!("{:?}", text?);
     }
 
 
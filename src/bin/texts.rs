@@ -0,0 +1,42 @@
+use vgc_data::*;
+use vgc_data::games::pokemon::export;
+use clap::Clap;
+
+#[derive(Clap)]
+struct Opts {
+    filename: String,
+    lang: String,
+    #[clap(short, long)]
+    json: bool,
+    // update CIA to apply on top of the game
+    #[clap(short, long)]
+    update: Option<String>,
+}
+
+fn main() -> Result<(), error::Error> {
+    let opts: Opts = Opts::parse();
+
+    let file = read::FileHolder::map(&opts.filename)?;
+    let update = match &opts.update {
+        Some(filename) => Some(read::FileHolder::open(filename)?),
+        None => None,
+    };
+
+    let game = match &update {
+        Some(update) => games::pokemon::Pokemon::with_update(file.reader(), update.reader())?,
+        None => games::pokemon::Pokemon::new(file.reader())?,
+    };
+
+    let language = games::pokemon::Language::from_tag(&opts.lang)
+        .ok_or_else(|| error::Error::unsupported(format!("unknown language {}", opts.lang)))?;
+
+    let lines = game.export_texts(language)?;
+
+    let stdout = std::io::stdout();
+    let mut output = stdout.lock();
+    if opts.json {
+        export::write_json(&lines, &mut output)
+    } else {
+        export::write_csv(&lines, &mut output)
+    }
+}
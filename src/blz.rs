@@ -0,0 +1,74 @@
+// Backwards LZ, used for the .code file in ExeFS. Data is decompressed from
+// the end towards the start, in place.
+
+use super::error::Error;
+
+fn error(message: &str) -> Error {
+    Error::malformed(message)
+}
+
+pub fn decompress(compressed: &[u8]) -> Result<Vec<u8>, Error> {
+    if compressed.len() < 8 {
+        return Err(error("BLZ data too short"));
+    }
+
+    let footer = &compressed[compressed.len() - 8..];
+    let buffer_top_and_bottom = u32::from_le_bytes([footer[0], footer[1], footer[2], footer[3]]) as usize;
+    let additional_size = u32::from_le_bytes([footer[4], footer[5], footer[6], footer[7]]) as usize;
+
+    let header_length = buffer_top_and_bottom >> 24;
+    let compressed_length = buffer_top_and_bottom & 0xFFFFFF;
+    if compressed_length > compressed.len() || header_length > compressed_length {
+        return Err(error("BLZ footer out of bounds"));
+    }
+
+    let mut decompressed = compressed.to_vec();
+    decompressed.resize(compressed.len() + additional_size, 0);
+
+    let stop = compressed.len() - compressed_length;
+    let mut index = compressed.len() - header_length;
+    let mut out = decompressed.len();
+
+    while index > stop {
+        index -= 1;
+        let mut control = compressed[index];
+
+        for _ in 0..8 {
+            if index <= stop || out == 0 {
+                break;
+            }
+
+            if control & 0x80 != 0 {
+                if index < stop + 2 {
+                    return Err(error("BLZ segment out of bounds"));
+                }
+
+                index -= 2;
+                let segment = u16::from_le_bytes([compressed[index], compressed[index + 1]]) as usize;
+                let size = ((segment >> 12) & 0xF) + 3;
+                let offset = (segment & 0x0FFF) + 2;
+
+                if out < size {
+                    return Err(error("BLZ segment out of bounds"));
+                }
+
+                for _ in 0..size {
+                    if out + offset >= decompressed.len() {
+                        return Err(error("BLZ segment out of bounds"));
+                    }
+
+                    decompressed[out - 1] = decompressed[out + offset];
+                    out -= 1;
+                }
+            } else {
+                index -= 1;
+                out -= 1;
+                decompressed[out] = compressed[index];
+            }
+
+            control <<= 1;
+        }
+    }
+
+    Ok(decompressed)
+}
@@ -0,0 +1,161 @@
+use super::error::Error;
+use super::ncch::NCCH;
+use super::read::Reader;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use byteorder::ReadBytesExt;
+use byteorder::LittleEndian;
+use byteorder::BigEndian;
+
+// Installable archive, the format updates and eShop titles come in. Only
+// decrypted contents can be read.
+#[derive(Debug)]
+pub struct CIA<'a> {
+    file: Reader<'a>,
+    header: Header,
+    contents: Vec<ContentChunk>,
+}
+
+pub type OwnedCIA = CIA<'static>;
+
+impl<'a> CIA<'a> {
+    pub fn new(mut file: Reader<'a>) -> Result<CIA<'a>, Error> {
+        let header = Header::read(&mut file)?;
+
+        file.seek(SeekFrom::Start(header.tmd_offset()))?;
+        let contents = read_tmd(&mut file)?;
+
+        Ok(CIA { file, header, contents })
+    }
+
+    pub fn content_count(&self) -> usize {
+        self.contents.len()
+    }
+
+    // Contents are stored back to back, in TMD order.
+    pub fn content(&self, index: usize) -> Result<Option<NCCH<'a>>, Error> {
+        let mut offset = self.header.content_offset();
+
+        for content in &self.contents {
+            if content.index as usize == index {
+                if content.content_type & 1 == 1 {
+                    return Err(Error::unsupported("encrypted CIA content"));
+                }
+
+                return NCCH::new(self.file.limit(offset, content.size)?).map(Option::Some);
+            }
+
+            offset = offset.checked_add(content.size)
+                .ok_or_else(|| Error::out_of_bounds("CIA content", offset, content.size, self.file.length()))?;
+        }
+
+        Ok(None)
+    }
+
+    // the game itself, or the whole update for update titles.
+    pub fn main_content(&self) -> Result<NCCH<'a>, Error> {
+        self.content(0)?.ok_or_else(|| Error::not_found("CIA content 0"))
+    }
+}
+
+impl<'a> super::read::VirtualFile<'a> for CIA<'a> {
+    fn reader(&self) -> Result<Reader<'a>, Error> {
+        Ok(self.file.at_zero())
+    }
+}
+
+#[derive(Default, Debug)]
+struct Header {
+    header_size: u32,
+    cia_type: u16,
+    version: u16,
+    certificate_chain_size: u32,
+    ticket_size: u32,
+    tmd_size: u32,
+    meta_size: u32,
+    content_size: u64,
+}
+
+impl Header {
+    fn read(input: &mut Reader) -> Result<Self, Error> {
+        let mut header = Header::default();
+
+        header.header_size = input.read_u32::<LittleEndian>()?;
+        header.cia_type = input.read_u16::<LittleEndian>()?;
+        header.version = input.read_u16::<LittleEndian>()?;
+        header.certificate_chain_size = input.read_u32::<LittleEndian>()?;
+        header.ticket_size = input.read_u32::<LittleEndian>()?;
+        header.tmd_size = input.read_u32::<LittleEndian>()?;
+        header.meta_size = input.read_u32::<LittleEndian>()?;
+        header.content_size = input.read_u64::<LittleEndian>()?;
+
+        if header.header_size != 0x2020 {
+            return Err(Error::UnsupportedVersion { what: "CIA header size", version: header.header_size as u64 });
+        }
+
+        Ok(header)
+    }
+
+    // every section starts on a 64 byte boundary.
+    fn tmd_offset(&self) -> u64 {
+        let certificates = align(self.header_size as u64);
+        let ticket = certificates + align(self.certificate_chain_size as u64);
+
+        ticket + align(self.ticket_size as u64)
+    }
+
+    fn content_offset(&self) -> u64 {
+        self.tmd_offset() + align(self.tmd_size as u64)
+    }
+}
+
+fn align(size: u64) -> u64 {
+    size.div_ceil(0x40) * 0x40
+}
+
+#[derive(Default, Debug)]
+struct ContentChunk {
+    id: u32,
+    index: u16,
+    content_type: u16,
+    size: u64,
+}
+
+// Title metadata, big endian. Only the content chunk records are kept.
+fn read_tmd(input: &mut Reader) -> Result<Vec<ContentChunk>, Error> {
+    let signature_size = match input.read_u32::<BigEndian>()? {
+        0x10000 | 0x10003 => 0x200 + 0x3C,
+        0x10001 | 0x10004 => 0x100 + 0x3C,
+        0x10002 | 0x10005 => 0x3C + 0x40,
+        signature_type => {
+            return Err(Error::UnsupportedVersion { what: "TMD signature type", version: signature_type as u64 });
+        },
+    };
+
+    input.seek(SeekFrom::Current(signature_size))?;
+
+    let mut header = [0; 0xC4];
+    input.read_exact(&mut header)?;
+    let content_count = u16::from_be_bytes([header[0x9E], header[0x9F]]);
+
+    // content info records
+    input.seek(SeekFrom::Current(0x24 * 64))?;
+
+    let mut contents = vec![];
+    for _ in 0..content_count {
+        let mut content = ContentChunk::default();
+
+        content.id = input.read_u32::<BigEndian>()?;
+        content.index = input.read_u16::<BigEndian>()?;
+        content.content_type = input.read_u16::<BigEndian>()?;
+        content.size = input.read_u64::<BigEndian>()?;
+
+        let mut hash = [0; 0x20];
+        input.read_exact(&mut hash)?;
+
+        contents.push(content);
+    }
+
+    Ok(contents)
+}
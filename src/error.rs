@@ -0,0 +1,94 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    // found is what was actually read at offset, in the container being parsed.
+    BadMagic { expected: Vec<u8>, found: Vec<u8>, offset: u64 },
+    // a region or index past the end of what contains it.
+    OutOfBounds { what: &'static str, offset: u64, length: u64, limit: u64 },
+    UnsupportedVersion { what: &'static str, version: u64 },
+    NotFound { path: String },
+    // the data is structurally wrong in a way the variants above don't cover.
+    Malformed(String),
+    // valid data that this crate can't handle, like encrypted contents or a
+    // file a game doesn't have.
+    Unsupported(String),
+    Io(std::io::Error),
+}
+
+impl Error {
+    pub fn bad_magic(expected: &[u8], found: &[u8], offset: u64) -> Self {
+        Error::BadMagic { expected: expected.to_vec(), found: found.to_vec(), offset }
+    }
+
+    pub fn out_of_bounds(what: &'static str, offset: u64, length: u64, limit: u64) -> Self {
+        Error::OutOfBounds { what, offset, length, limit }
+    }
+
+    pub fn not_found(path: &str) -> Self {
+        Error::NotFound { path: path.into() }
+    }
+
+    pub fn malformed<S: Into<String>>(message: S) -> Self {
+        Error::Malformed(message.into())
+    }
+
+    pub fn unsupported<S: Into<String>>(message: S) -> Self {
+        Error::Unsupported(message.into())
+    }
+}
+
+// ASCII magic numbers are printed as text, anything else as hex.
+fn magic(bytes: &[u8]) -> String {
+    if bytes.iter().all(|b| b.is_ascii_graphic()) {
+        String::from_utf8_lossy(bytes).into()
+    } else {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::BadMagic { expected, found, offset } => {
+                write!(f, "bad magic number at {:#x}: expected {}, found {}", offset, magic(expected), magic(found))
+            },
+            Error::OutOfBounds { what, offset, length, limit } => {
+                write!(f, "{} out of bounds: {:#x}+{:#x} past {:#x}", what, offset, length, limit)
+            },
+            Error::UnsupportedVersion { what, version } => write!(f, "unsupported {} version {:#x}", what, version),
+            Error::NotFound { path } => write!(f, "{} not found", path),
+            Error::Malformed(message) => write!(f, "malformed data: {}", message),
+            Error::Unsupported(message) => write!(f, "unsupported: {}", message),
+            Error::Io(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+// Lets `?` keep working in code that deals in io errors, like Read impls and
+// the tools in src/bin.
+impl From<Error> for std::io::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Io(e) => e,
+            Error::NotFound { .. } => std::io::Error::new(std::io::ErrorKind::NotFound, e),
+            Error::Unsupported(_) | Error::UnsupportedVersion { .. } => std::io::Error::other(e),
+            _ => std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        }
+    }
+}
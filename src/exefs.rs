@@ -0,0 +1,95 @@
+use super::error::Error;
+use super::read::Reader;
+use super::read::VirtualFile;
+use std::io::Read;
+use byteorder::ReadBytesExt;
+use byteorder::LittleEndian;
+
+#[derive(Debug)]
+pub struct ExeFS<'a> {
+    file: Reader<'a>,
+    header: Header,
+}
+
+pub type OwnedExeFS = ExeFS<'static>;
+
+impl<'a> ExeFS<'a> {
+    pub fn new(mut file: Reader<'a>) -> Result<ExeFS<'a>, Error> {
+        let header = Header::read(&mut file)?;
+
+        Ok(ExeFS { file, header })
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &FileEntry> {
+        self.header.files.iter().filter(|entry| entry.size > 0)
+    }
+
+    pub fn file_at(&self, name: &str) -> Result<Option<Reader<'a>>, Error> {
+        match self.entries().find(|entry| entry.name() == name) {
+            Some(entry) => Ok(Some(self.file.limit(0x200 + entry.offset as u64, entry.size as u64)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<'a> VirtualFile<'a> for ExeFS<'a> {
+    fn reader(&self) -> Result<Reader<'a>, Error> {
+        Ok(self.file.at_zero())
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct FileEntry {
+    name: [u8; 8],
+    offset: u32,
+    size: u32,
+}
+
+impl FileEntry {
+    pub fn name(&self) -> String {
+        String::from_utf8_lossy(&self.name).trim_end_matches('\x00').into()
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+}
+
+#[derive(Default, Debug)]
+struct Header {
+    files: Vec<FileEntry>,
+    reserved: [u8; 0x20],
+    hashes: Vec<[u8; 0x20]>,
+}
+
+impl Header {
+    fn read(input: &mut Reader) -> Result<Header, Error> {
+        let mut header = Header::default();
+
+        for _ in 0..10 {
+            let mut entry = FileEntry::default();
+
+            input.read_exact(&mut entry.name)?;
+            entry.offset = input.read_u32::<LittleEndian>()?;
+            entry.size = input.read_u32::<LittleEndian>()?;
+
+            if entry.size > 0 && 0x200 + entry.offset as u64 + entry.size as u64 > input.length() {
+                return Err(Error::out_of_bounds("ExeFS file", 0x200 + entry.offset as u64, entry.size as u64, input.length()));
+            }
+
+            header.files.push(entry);
+        }
+
+        input.read_exact(&mut header.reserved)?;
+
+        // stored in reverse order, the last hash is for the first file.
+        for _ in 0..10 {
+            let mut hash = [0; 0x20];
+            input.read_exact(&mut hash)?;
+            header.hashes.push(hash);
+        }
+        header.hashes.reverse();
+
+        Ok(header)
+    }
+}
@@ -0,0 +1,136 @@
+use super::error::Error;
+use super::garc::GARC;
+use super::read::Reader;
+use super::read::VirtualFile;
+use super::romfs::Node;
+use super::romfs::RomFS;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+// One file to write out. Readers are Send + Sync, so jobs can be handed to
+// any thread.
+#[derive(Debug, Clone)]
+pub struct Job<'a> {
+    pub path: PathBuf,
+    pub reader: Reader<'a>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Progress<'b> {
+    pub done: usize,
+    pub total: usize,
+    pub path: &'b Path,
+}
+
+// Every file of a RomFS, under destination. Directories are created as they
+// are found, so jobs can run in any order.
+pub fn romfs_jobs<'a>(romfs: &RomFS<'a>, destination: &Path) -> Result<Vec<Job<'a>>, Error> {
+    let mut jobs = vec![];
+
+    std::fs::create_dir_all(destination)?;
+    for entry in romfs.walk()? {
+        let (path, node) = entry?;
+        if !is_plain_name(node.basename()) {
+            return Err(Error::malformed(format!("RomFS entry {:?} would be written outside its directory", path)));
+        }
+
+        match node {
+            Node::File(file) => jobs.push(Job { path: destination.join(path), reader: file.reader()? }),
+            Node::Directory(_) => std::fs::create_dir_all(destination.join(path))?,
+        }
+    }
+
+    Ok(jobs)
+}
+
+// Every subfile of a GARC, named {prefix}.{entry}.{subentry} like the garc tool does.
+pub fn garc_jobs<'a>(garc: &GARC<'a>, prefix: &str) -> Result<Vec<Job<'a>>, Error> {
+    let width = (garc.file_count() as f64).log10().ceil() as usize;
+    let mut jobs = vec![];
+
+    let mut it = garc.entries();
+    while let Some(entry) = it.try_next()? {
+        let mut jt = entry.entries();
+        while let Some(subentry) = jt.try_next()? {
+            jobs.push(Job {
+                path: format!("{}.{:0width$}.{:02}", prefix, entry.index(), subentry.index(), width = width).into(),
+                reader: subentry.reader()?,
+            });
+        }
+    }
+
+    Ok(jobs)
+}
+
+// Writes every job's file using `threads` workers. progress is called after
+// each file, from whichever worker wrote it. Stops at the first error.
+pub fn extract<F>(jobs: &[Job], threads: usize, progress: F) -> Result<(), Error>
+    where F: Fn(Progress) + Sync
+{
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let failure = Mutex::new(None);
+
+    let worker = || {
+        loop {
+            if failure.lock().map(|failure| failure.is_some()).unwrap_or(true) {
+                return;
+            }
+
+            let job = match jobs.get(next.fetch_add(1, Ordering::SeqCst)) {
+                Some(job) => job,
+                None => { return; },
+            };
+
+            match save(job) {
+                Ok(()) => {
+                    let done = done.fetch_add(1, Ordering::SeqCst) + 1;
+                    progress(Progress { done, total: jobs.len(), path: &job.path });
+                },
+                Err(e) => {
+                    if let Ok(mut failure) = failure.lock() {
+                        failure.get_or_insert(e);
+                    }
+                    return;
+                },
+            }
+        }
+    };
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(worker);
+        }
+    });
+
+    match failure.into_inner().ok().flatten() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+// One worker per available core.
+pub fn default_threads() -> usize {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+// Names come from the image, so they only get joined onto the destination
+// when they can't climb out of it.
+fn is_plain_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+
+    !name.contains(['/', '\\', '\0'])
+        && matches!(components.next(), Some(Component::Normal(component)) if component == name)
+        && components.next().is_none()
+}
+
+fn save(job: &Job) -> Result<(), Error> {
+    let mut reader = job.reader.clone();
+    std::io::copy(&mut reader, &mut std::fs::File::create(&job.path)?)?;
+
+    Ok(())
+}
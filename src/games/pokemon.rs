@@ -1,15 +1,32 @@
+pub mod code;
+pub mod export;
+pub mod files;
+
+use super::super::error::Error;
 use super::super::*;
 use super::super::read::Reader;
 use super::super::read::VirtualFile;
+use super::super::pokemon::table::Record;
 
 #[derive(Debug)]
 pub struct Pokemon<'a> {
-    romfs: super::super::romfs::RomFS<'a>,
+    partition: ncch::NCCH<'a>,
+    update: Option<ncch::NCCH<'a>>,
+    romfs: overlay::Overlay<'a>,
     product: Game,
+    info: GameInfo,
 }
 
-#[derive(Debug)]
+// Owns its file, so it can live in a long-running service's state and be
+// shared between threads behind an Arc. See `Pokemon::open`.
+pub type OwnedPokemon = Pokemon<'static>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Game {
+    X,
+    Y,
+    OmegaRuby,
+    AlphaSapphire,
     Sun,
     Moon,
     UltraSun,
@@ -18,15 +35,94 @@ pub enum Game {
 
 impl Game {
     pub fn from_product_code(code: &str) -> Option<Self> {
+        TITLES.iter().find(|title| Some(title.game_code) == code.get(6..9)).map(|title| title.game)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    All,
+    Japan,
+    America,
+    Europe,
+    Korea,
+    China,
+    Taiwan,
+}
+
+impl Region {
+    // last letter of the product code.
+    pub fn from_code(code: char) -> Option<Self> {
         match code {
-            "CTR-P-BNDA" => Some(Self::Sun),
-            "CTR-P-BNEA" => Some(Self::Moon),
-            "CTR-P-A2AA" => Some(Self::UltraSun),
+            'A' => Some(Self::All),
+            'J' => Some(Self::Japan),
+            'E' => Some(Self::America),
+            'P' => Some(Self::Europe),
+            'K' => Some(Self::Korea),
+            'C' => Some(Self::China),
+            'W' => Some(Self::Taiwan),
             _ => None,
         }
     }
 }
 
+struct Title {
+    unique_id: u32,
+    game_code: &'static str,
+    game: Game,
+    name: &'static str,
+    generation: u8,
+}
+
+// These were released worldwide, with a single title for every region.
+const TITLES: &[Title] = &[
+    Title { unique_id: 0x0055D, game_code: "EKJ", game: Game::X, name: "Pokémon X", generation: 6 },
+    Title { unique_id: 0x0055E, game_code: "EK2", game: Game::Y, name: "Pokémon Y", generation: 6 },
+    Title { unique_id: 0x011C4, game_code: "ECR", game: Game::OmegaRuby, name: "Pokémon Omega Ruby", generation: 6 },
+    Title { unique_id: 0x011C5, game_code: "ECL", game: Game::AlphaSapphire, name: "Pokémon Alpha Sapphire", generation: 6 },
+    Title { unique_id: 0x01648, game_code: "BND", game: Game::Sun, name: "Pokémon Sun", generation: 7 },
+    Title { unique_id: 0x0175E, game_code: "BNE", game: Game::Moon, name: "Pokémon Moon", generation: 7 },
+    Title { unique_id: 0x01B50, game_code: "A2A", game: Game::UltraSun, name: "Pokémon Ultra Sun", generation: 7 },
+    Title { unique_id: 0x01B51, game_code: "A2B", game: Game::UltraMoon, name: "Pokémon Ultra Moon", generation: 7 },
+];
+
+#[derive(Debug, Clone)]
+pub struct GameInfo {
+    pub game: Game,
+    pub title: &'static str,
+    pub generation: u8,
+    pub region: Region,
+    pub revision: u16,
+    pub update: bool,
+}
+
+impl GameInfo {
+    // Program ids look like 0004000000164800 for the game and 0004000E00164800
+    // for its update. The product code is CTR-P-BNDA, with the region last.
+    pub fn detect(partition: &ncch::NCCH) -> Result<Self, Error> {
+        let program_id = partition.program_id();
+        let product_code = partition.product_code()?;
+
+        let unsupported = || Error::unsupported(format!("title {:016X} ({})", program_id, product_code));
+
+        let title = TITLES.iter()
+            .find(|title| title.unique_id == ((program_id >> 8) & 0xFFFFF) as u32)
+            .filter(|title| product_code.get(6..9) == Some(title.game_code))
+            .ok_or_else(unsupported)?;
+
+        let region = product_code.chars().nth(9).and_then(Region::from_code).ok_or_else(unsupported)?;
+
+        Ok(GameInfo {
+            game: title.game,
+            title: title.name,
+            generation: title.generation,
+            region,
+            revision: partition.remaster_version()?,
+            update: (program_id >> 32) & 0xFF == 0x0E,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Language {
     Japanese = 0,
@@ -41,93 +137,522 @@ pub enum Language {
     ChineseTraditional = 9,
 }
 
+impl Language {
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "ja" => Some(Self::Japanese),
+            "ja-hrkt" => Some(Self::JapaneseHiragana),
+            "en" => Some(Self::English),
+            "fr" => Some(Self::French),
+            "it" => Some(Self::Italian),
+            "de" => Some(Self::German),
+            "es" => Some(Self::Spanish),
+            "ko" => Some(Self::Korean),
+            "zh-hans" => Some(Self::ChineseSimplified),
+            "zh-hant" => Some(Self::ChineseTraditional),
+            _ => None,
+        }
+    }
+}
+
+// Text files in the common text archive for each language.
+#[derive(Debug, Clone, Copy)]
+pub enum TextFile {
+    ItemDescriptions,
+    ItemNames,
+    ItemPluralNames,
+    SpeciesNames,
+    LocationNames,
+    Natures,
+    AbilityNames,
+    AbilityDescriptions,
+    TrainerText,
+    TrainerNames,
+    TrainerClasses,
+    Types,
+    MoveDescriptions,
+    MoveNames,
+    FormNames,
+    SpeciesClassifications,
+    PokedexEntries,
+    AltPokedexEntries,
+}
+
+impl TextFile {
+    pub fn index(&self, game: &Game) -> Option<usize> {
+        let (x_y, omega_ruby_alpha_sapphire, sun_moon, ultra_sun_moon) = match self {
+            Self::ItemDescriptions => (Some(99), Some(117), 35, 39),
+            Self::ItemNames => (Some(96), Some(114), 36, 40),
+            Self::ItemPluralNames => (None, None, 37, 41),
+            Self::SpeciesNames => (Some(80), Some(98), 55, 60),
+            Self::LocationNames => (Some(72), Some(90), 67, 72),
+            Self::Natures => (Some(47), Some(51), 87, 92),
+            Self::AbilityNames => (Some(34), Some(37), 96, 101),
+            Self::AbilityDescriptions => (None, None, 97, 102),
+            Self::TrainerText => (Some(22), Some(23), 104, 109),
+            Self::TrainerNames => (Some(21), Some(22), 105, 110),
+            Self::TrainerClasses => (Some(20), Some(21), 106, 111),
+            Self::Types => (Some(17), Some(18), 107, 112),
+            Self::MoveDescriptions => (Some(15), Some(16), 112, 117),
+            Self::MoveNames => (Some(13), Some(14), 113, 118),
+            Self::FormNames => (Some(5), Some(5), 114, 119),
+            Self::SpeciesClassifications => (None, None, 116, 121),
+            Self::PokedexEntries => (None, None, 119, 124),
+            Self::AltPokedexEntries => (None, None, 120, 125),
+        };
+
+        match game {
+            Game::X | Game::Y => x_y,
+            Game::OmegaRuby | Game::AlphaSapphire => omega_ruby_alpha_sapphire,
+            Game::Sun | Game::Moon => Some(sun_moon),
+            Game::UltraSun | Game::UltraMoon => Some(ultra_sun_moon),
+        }
+    }
+}
+
 impl<'a> Pokemon<'a> {
-    pub fn new(file: Reader<'a>) -> Result<Self, std::io::Error> {
+    pub fn new(file: Reader<'a>) -> Result<Self, Error> {
         let ncsd = ncsd::NCSD::new(file)?;
-        let romfs = ncsd.partition(ncsd::Partition::Main)?.romfs()?.unwrap();
-        let code = ncsd.partition(ncsd::Partition::Main)?.product_code().unwrap();
+        let partition = ncsd.partition(ncsd::Partition::Main)?;
+        let info = GameInfo::detect(&partition)?;
+        let romfs = overlay::Overlay::new(&partition, None)?;
 
         Ok(Self {
+            partition,
+            update: None,
             romfs,
-            product: Game::from_product_code(&code).unwrap(),
+            product: info.game,
+            info,
         })
     }
 
-    pub fn pokemon_names(&self, language: Language) -> Result<pokemon::text::Texts, std::io::Error> {
-        self.text_entries(&format!("a/0/3/{}", language as u8), 55, 0)
+    // Maps the game's file and keeps it for as long as the handle lives.
+    pub fn open(filename: &str) -> Result<OwnedPokemon, Error> {
+        Pokemon::new(read::FileHolder::map(filename)?.into_shared())
     }
 
-    pub fn pokedex_entries(&self, language: Language) -> Result<pokemon::text::Texts, std::io::Error> {
-        let offset = match self.product {
-            Game::Sun | Game::Moon => 119,
-            Game::UltraSun | Game::UltraMoon => 124,
-        };
+    // The game with an update title (a CIA) applied on top. Files and code
+    // come from the update when it has them.
+    pub fn with_update(file: Reader<'a>, update: Reader<'a>) -> Result<Self, Error> {
+        let ncsd = ncsd::NCSD::new(file)?;
+        let partition = ncsd.partition(ncsd::Partition::Main)?;
+        let mut info = GameInfo::detect(&partition)?;
+
+        let update = cia::CIA::new(update)?.main_content()?;
+        let update_info = GameInfo::detect(&update)?;
+        if update_info.game != info.game || !update_info.update {
+            return Err(Error::unsupported(format!("{:016X} isn't an update for {}", update.program_id(), info.title)));
+        }
+        info.revision = update_info.revision;
+
+        let romfs = overlay::Overlay::new(&partition, Some(&update))?;
 
-        self.text_entries(&format!("a/0/3/{}", language as u8), offset, 0)
+        Ok(Self {
+            partition,
+            update: Some(update),
+            romfs,
+            product: info.game,
+            info,
+        })
     }
 
-    pub fn alt_pokedex_entries(&self, language: Language) -> Result<pokemon::text::Texts, std::io::Error> {
-        let offset = match self.product {
-            Game::Sun | Game::Moon => 120,
-            Game::UltraSun | Game::UltraMoon => 125,
-        };
+    pub fn info(&self) -> &GameInfo {
+        &self.info
+    }
 
-        self.text_entries(&format!("a/0/3/{}", language as u8), offset, 0)
+    pub fn pokemon_names(&self, language: Language) -> Result<pokemon::text::Texts<'_>, Error> {
+        self.text_file(TextFile::SpeciesNames, language)
     }
 
-    pub fn species_names(&self, language: Language) -> Result<pokemon::text::Texts, std::io::Error> {
-        let offset = match self.product {
-            Game::Sun | Game::Moon => 55,
-            Game::UltraSun | Game::UltraMoon => 60,
-        };
+    pub fn pokedex_entries(&self, language: Language) -> Result<pokemon::text::Texts<'_>, Error> {
+        self.text_file(TextFile::PokedexEntries, language)
+    }
 
-        self.text_entries(&format!("a/0/3/{}", language as u8), offset, 0)
+    pub fn alt_pokedex_entries(&self, language: Language) -> Result<pokemon::text::Texts<'_>, Error> {
+        self.text_file(TextFile::AltPokedexEntries, language)
     }
 
+    pub fn species_names(&self, language: Language) -> Result<pokemon::text::Texts<'_>, Error> {
+        self.text_file(TextFile::SpeciesNames, language)
+    }
 
-    pub fn form_names(&self, language: Language) -> Result<pokemon::text::Texts, std::io::Error> {
-        let offset = match self.product {
-            Game::Sun | Game::Moon => 114,
-            Game::UltraSun | Game::UltraMoon => 119,
-        };
 
-        self.text_entries(&format!("a/0/3/{}", language as u8), offset, 0)
+    pub fn form_names(&self, language: Language) -> Result<pokemon::text::Texts<'_>, Error> {
+        self.text_file(TextFile::FormNames, language)
     }
 
-    pub fn form_linked_list(&self) -> Result<pokemon::table::Table, std::io::Error> {
-        let offset = match self.product {
-            Game::Sun | Game::Moon => 2,
-            Game::UltraSun | Game::UltraMoon => 6,
-        };
+    pub fn form_linked_list(&self) -> Result<pokemon::table::Table<'_>, Error> {
+        let form_links = self.file(self.files().form_links, "form links")?;
+        self.table_entries(&form_links, 1, 0)
+    }
 
-        self.table_entries(&format!("a/1/5/{}", offset), 1, 0)
+    pub fn move_names(&self, language: Language) -> Result<pokemon::text::Texts<'_>, Error> {
+        self.text_file(TextFile::MoveNames, language)
     }
 
-    pub fn text_entries(&self, filename: &str, idx: usize, subidx: usize) -> Result<pokemon::text::Texts, std::io::Error> {
-        let garc = match self.romfs.file_at(filename)? {
-            Some(romfs::Node::File(f)) => f,
-            _ => { panic!("derp"); },
-        };
+    pub fn move_descriptions(&self, language: Language) -> Result<pokemon::text::Texts<'_>, Error> {
+        self.text_file(TextFile::MoveDescriptions, language)
+    }
 
-        let file = match garc::GARC::new(garc.reader())?.file_at(idx, subidx)? {
-            Some(file) => file,
-            None => { panic!("Couldn't find garc subfile"); },
+    pub fn moves(&self, language: Language) -> Result<Vec<MoveEntry>, Error> {
+        let names = self.move_names(language)?.entries().collect::<Result<Vec<_>, _>>()?;
+        let descriptions = self.move_descriptions(language)?.entries().collect::<Result<Vec<_>, _>>()?;
+        let files = self.files();
+        let moves = match (files.moves_packed, self.info.generation) {
+            (true, 7) => self.mini_entries(&files::path(files.moves), 0, 0, *b"WD")?.records::<pokemon::moves::Move>()?,
+            (true, _) => {
+                let pack = self.mini_entries(&files::path(files.moves), 0, 0, *b"WD")?;
+                let mut moves = vec![];
+                for i in 0..pack.len() as usize {
+                    moves.push(pokemon::moves::Move::read_gen6(&mut pack.entry(i)?)?);
+                }
+                moves
+            },
+            (false, _) => {
+                let garc = self.garc(&files::path(files.moves))?;
+                let mut moves = vec![];
+
+                let mut it = garc.entries();
+                while let Some(entry) = it.try_next()? {
+                    if let Some(subentry) = entry.entries().try_next()? {
+                        moves.push(pokemon::moves::Move::read_gen6(&mut subentry.reader()?)?);
+                    }
+                }
+                moves
+            },
         };
 
-        pokemon::text::Texts::new(file.reader())
+        Ok(moves.into_iter().enumerate().map(|(id, data)| MoveEntry {
+            id: id as u16,
+            name: names.get(id).cloned().unwrap_or_default(),
+            description: descriptions.get(id).cloned().unwrap_or_default(),
+            data,
+        }).collect())
     }
 
-    pub fn table_entries(&self, filename: &str, idx: usize, subidx: usize) -> Result<pokemon::table::Table, std::io::Error> {
+    pub fn ability_names(&self, language: Language) -> Result<pokemon::text::Texts<'_>, Error> {
+        self.text_file(TextFile::AbilityNames, language)
+    }
+
+    pub fn ability_descriptions(&self, language: Language) -> Result<pokemon::text::Texts<'_>, Error> {
+        self.text_file(TextFile::AbilityDescriptions, language)
+    }
+
+    pub fn item_names(&self, language: Language) -> Result<pokemon::text::Texts<'_>, Error> {
+        self.text_file(TextFile::ItemNames, language)
+    }
+
+    pub fn item_plural_names(&self, language: Language) -> Result<pokemon::text::Texts<'_>, Error> {
+        self.text_file(TextFile::ItemPluralNames, language)
+    }
+
+    pub fn item_descriptions(&self, language: Language) -> Result<pokemon::text::Texts<'_>, Error> {
+        self.text_file(TextFile::ItemDescriptions, language)
+    }
+
+    pub fn items(&self, language: Language) -> Result<Vec<ItemEntry>, Error> {
+        let names = self.item_names(language)?.entries().collect::<Result<Vec<_>, _>>()?;
+        let plural_names = self.optional_text_entries(TextFile::ItemPluralNames, language)?;
+        let descriptions = self.item_descriptions(language)?.entries().collect::<Result<Vec<_>, _>>()?;
+
+        let garc = self.garc(&files::path(self.files().items))?;
+        let mut items = vec![];
+
+        let mut it = garc.entries();
+        while let Some(entry) = it.try_next()? {
+            let id = entry.index() as usize;
+            let data = match entry.entries().try_next()? {
+                Some(subentry) => pokemon::items::Item::read(&mut subentry.reader()?)?,
+                None => continue,
+            };
+
+            items.push(ItemEntry {
+                id: id as u16,
+                name: names.get(id).cloned().unwrap_or_default(),
+                plural_name: plural_names.get(id).cloned().unwrap_or_default(),
+                description: descriptions.get(id).cloned().unwrap_or_default(),
+                data,
+            });
+        }
+
+        Ok(items)
+    }
+
+    pub fn encounters(&self, language: Language) -> Result<Vec<ZoneEncounters>, Error> {
+        let location_names = self.text_file(TextFile::LocationNames, language)?.entries().collect::<Result<Vec<_>, _>>()?;
+        let files = self.files();
+        let zones = self.file(files.zones, "zones")?;
+        let zones = pokemon::table::read_records::<pokemon::encounters::Zone>(&mut self.subfile(&zones, 1, 0)?)?;
+
+        let world = self.garc(&self.file(files.world_data, "world data")?)?;
+        let encounters = self.garc(&self.file(files.wild_encounters, "wild encounters")?)?;
+
+        let mut result = vec![];
+
+        let mut it = world.entries();
+        while let Some(entry) = it.try_next()? {
+            let area = entry.index() as usize;
+            let world_file = match entry.entries().try_next()? {
+                Some(subentry) => subentry,
+                None => continue,
+            };
+
+            let area_file = match encounters.file_at(9 + 11 * area, 0)? {
+                Some(file) if file.reader()?.length() > 0 => file,
+                _ => continue,
+            };
+
+            let area_zones = pokemon::table::Table::new(world_file.reader()?)?.u16s(0)?;
+            let area_tables = pokemon::table::Table::new(area_file.reader()?)?;
+
+            for (i, zone) in area_zones.iter().enumerate() {
+                if i >= area_tables.len() as usize {
+                    break;
+                }
+
+                let tables = area_tables.records::<pokemon::encounters::EncounterTable>(i)?;
+                let location_name = zones.get(*zone as usize)
+                    .and_then(|zone| location_names.get(zone.location_name_index() as usize))
+                    .cloned()
+                    .unwrap_or_default();
+
+                result.push(ZoneEncounters {
+                    zone: *zone,
+                    area: area as u16,
+                    location_name,
+                    tables: tables.chunks(2).filter(|pair| pair.len() == 2).map(|pair| EncounterTables {
+                        day: pair[0].clone(),
+                        night: pair[1].clone(),
+                    }).collect(),
+                });
+            }
+        }
+
+        Ok(result)
+    }
+
+    pub fn trainers(&self, language: Language) -> Result<Vec<TrainerEntry>, Error> {
+        let names = self.text_file(TextFile::TrainerNames, language)?.entries().collect::<Result<Vec<_>, _>>()?;
+        let class_names = self.text_file(TextFile::TrainerClasses, language)?.entries().collect::<Result<Vec<_>, _>>()?;
+
+        let files = self.files();
+        let teams = self.garc(&self.file(files.trainer_teams, "trainer teams")?)?;
+
+        let mut trainers = vec![];
+
+        let garc = self.garc(&self.file(files.trainer_data, "trainer data")?)?;
+        let mut it = garc.entries();
+        while let Some(entry) = it.try_next()? {
+            let id = entry.index() as usize;
+            let data = match entry.entries().try_next()? {
+                Some(subentry) => pokemon::trainers::Trainer::read(&mut subentry.reader()?)?,
+                None => continue,
+            };
+
+            let team = match teams.file_at(id, 0)? {
+                Some(file) => pokemon::table::read_records::<pokemon::trainers::TrainerPokemon>(&mut file.reader()?)?,
+                None => vec![],
+            };
+
+            trainers.push(TrainerEntry {
+                id: id as u16,
+                name: names.get(id).cloned().unwrap_or_default(),
+                class_name: class_names.get(data.class as usize).cloned().unwrap_or_default(),
+                data,
+                team,
+            });
+        }
+
+        Ok(trainers)
+    }
+
+    pub fn static_encounters(&self) -> Result<StaticEncounters, Error> {
+        let filename = self.file(self.files().static_encounters, "static encounters")?;
+
+        Ok(StaticEncounters {
+            gifts: pokemon::table::read_records(&mut self.subfile(&filename, 0, 0)?)?,
+            encounters: pokemon::table::read_records(&mut self.subfile(&filename, 1, 0)?)?,
+            trades: pokemon::table::read_records(&mut self.subfile(&filename, 2, 0)?)?,
+        })
+    }
+
+    pub fn personal(&self) -> Result<Vec<pokemon::personal::Personal>, Error> {
+        let files = self.files();
+        let garc = self.garc(&files::path(files.personal))?;
+        let mut personal = vec![];
+
+        let mut it = garc.entries();
+        while let Some(entry) = it.try_next()? {
+            let mut file = match entry.entries().try_next()? {
+                Some(subentry) => subentry.reader()?,
+                None => continue,
+            };
+
+            // The last entry repeats every record in one file.
+            if file.length() != files.personal_size as u64 {
+                continue;
+            }
+
+            personal.push(pokemon::personal::Personal::read(&mut file, files.personal_size)?);
+        }
+
+        Ok(personal)
+    }
+
+    // Indexed like personal info: species first, then alternate forms from
+    // each species' form stats index.
+    pub fn learnsets(&self) -> Result<Vec<pokemon::learnsets::Learnset>, Error> {
+        let garc = self.garc(&files::path(self.files().learnsets))?;
+        let mut learnsets = vec![];
+
+        let mut it = garc.entries();
+        while let Some(entry) = it.try_next()? {
+            let learnset = match entry.entries().try_next()? {
+                Some(subentry) => pokemon::learnsets::Learnset::read(&mut subentry.reader()?)?,
+                None => Default::default(),
+            };
+
+            learnsets.push(learnset);
+        }
+
+        Ok(learnsets)
+    }
+
+    pub fn tm_moves(&self) -> Result<Vec<u16>, Error> {
+        self.require_generation(7, "TM moves")?;
+        code::TM_MOVES.read(&self.code()?)
+    }
+
+    // Sun and Moon only have the starter and dragon tutors, which are
+    // hard-coded in their scripts rather than kept in a table.
+    pub fn tutor_moves(&self) -> Result<Vec<u16>, Error> {
+        match self.product {
+            Game::UltraSun | Game::UltraMoon => code::BP_TUTOR_MOVES.read(&self.code()?),
+            _ => Err(self.unsupported("tutor moves")),
+        }
+    }
+
+    // Entries of a text file that only some generations have; empty where
+    // the game doesn't have it at all.
+    fn optional_text_entries(&self, file: TextFile, language: Language) -> Result<Vec<String>, Error> {
+        if file.index(&self.product).is_none() {
+            return Ok(vec![]);
+        }
+
+        self.text_file(file, language)?.entries().collect()
+    }
+
+    pub fn code(&self) -> Result<Vec<u8>, Error> {
+        if let Some(code) = self.update.as_ref().map(ncch::NCCH::code).transpose()?.flatten() {
+            return Ok(code);
+        }
+
+        self.partition.code()?.ok_or_else(|| Error::not_found("ExeFS/.code"))
+    }
+
+    pub fn text_file(&self, file: TextFile, language: Language) -> Result<pokemon::text::Texts<'_>, Error> {
+        let index = file.index(&self.product).ok_or_else(|| self.unsupported(&format!("{:?}", file)))?;
+        self.text_entries(&self.files().common_text(language)?, index, 0)
+    }
+
+    pub fn files(&self) -> &'static files::Files {
+        self.product.files()
+    }
+
+    pub fn text_entries(&self, filename: &str, idx: usize, subidx: usize) -> Result<pokemon::text::Texts<'_>, Error> {
+        pokemon::text::Texts::new(self.subfile(filename, idx, subidx)?)
+    }
+
+    pub fn table_entries(&self, filename: &str, idx: usize, subidx: usize) -> Result<pokemon::table::Table<'_>, Error> {
+        pokemon::table::Table::new(self.subfile(filename, idx, subidx)?)
+    }
+
+    pub fn mini_entries(&self, filename: &str, idx: usize, subidx: usize, magic: [u8; 2]) -> Result<pokemon::mini::Mini<'_>, Error> {
+        pokemon::mini::Mini::new(self.subfile(filename, idx, subidx)?, magic)
+    }
+
+    fn file(&self, garc: Option<usize>, what: &str) -> Result<String, Error> {
+        garc.map(files::path).ok_or_else(|| self.unsupported(what))
+    }
+
+    fn require_generation(&self, generation: u8, what: &str) -> Result<(), Error> {
+        if self.info.generation != generation {
+            return Err(self.unsupported(what));
+        }
+
+        Ok(())
+    }
+
+    fn unsupported(&self, what: &str) -> Error {
+        Error::unsupported(format!("{} in {}", what, self.info.title))
+    }
+
+    fn garc(&self, filename: &str) -> Result<garc::GARC<'a>, Error> {
         let garc = match self.romfs.file_at(filename)? {
             Some(romfs::Node::File(f)) => f,
-            _ => { panic!("derp"); },
+            _ => { return Err(Error::not_found(filename)); },
         };
 
-        let file = match garc::GARC::new(garc.reader())?.file_at(idx, subidx)? {
+        garc::GARC::new(garc.reader()?)
+    }
+
+    fn subfile(&self, filename: &str, idx: usize, subidx: usize) -> Result<Reader<'a>, Error> {
+        let file = match self.garc(filename)?.file_at(idx, subidx)? {
             Some(file) => file,
-            None => { panic!("Couldn't find garc subfile"); },
+            None => { return Err(Error::not_found(&format!("{}.{}.{:02}", filename, idx, subidx))); },
         };
 
-        pokemon::table::Table::new(file.reader())
+        file.reader()
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct MoveEntry {
+    pub id: u16,
+    pub name: String,
+    pub description: String,
+    pub data: pokemon::moves::Move,
+}
+
+// Tables come in day and night pairs. Their position in the zone decides
+// whether they're for grass, surfing, fishing, etc, which isn't stored.
+#[derive(Debug, Clone)]
+pub struct EncounterTables {
+    pub day: pokemon::encounters::EncounterTable,
+    pub night: pokemon::encounters::EncounterTable,
+}
+
+#[derive(Debug, Clone)]
+pub struct ZoneEncounters {
+    pub zone: u16,
+    pub area: u16,
+    pub location_name: String,
+    pub tables: Vec<EncounterTables>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrainerEntry {
+    pub id: u16,
+    pub name: String,
+    pub class_name: String,
+    pub data: pokemon::trainers::Trainer,
+    pub team: Vec<pokemon::trainers::TrainerPokemon>,
+}
+
+// Each list is a GARC entry of its own. Use `pokemon::table::write_records`
+// to encode an edited list back.
+#[derive(Debug, Clone)]
+pub struct StaticEncounters {
+    pub gifts: Vec<pokemon::static_encounters::Gift>,
+    pub encounters: Vec<pokemon::static_encounters::StaticEncounter>,
+    pub trades: Vec<pokemon::static_encounters::Trade>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ItemEntry {
+    pub id: u16,
+    pub name: String,
+    pub plural_name: String,
+    pub description: String,
+    pub data: pokemon::items::Item,
+}
@@ -0,0 +1,59 @@
+// Tables inside the decompressed .code. Their offsets move around between
+// games and revisions, so they're found by looking for their first entries.
+
+use super::super::super::error::Error;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Locator {
+    signature: &'static [u16],
+    count: usize,
+}
+
+// TM01 Work Up, TM02 Dragon Claw, TM03 Psyshock, TM04 Calm Mind, TM05 Roar, ...
+pub const TM_MOVES: Locator = Locator {
+    signature: &[526, 337, 473, 347, 46, 92, 258, 339, 474, 237],
+    count: 100,
+};
+
+// Ultra Sun and Ultra Moon BP tutors, in the same order as the tutor bits in
+// the personal information: Bug Bite, Covet, Super Fang, Dual Chop, ...
+pub const BP_TUTOR_MOVES: Locator = Locator {
+    signature: &[450, 343, 162, 530, 324, 442, 402, 529, 340, 67],
+    count: 67,
+};
+
+impl Locator {
+    pub fn find(&self, code: &[u8]) -> Option<usize> {
+        let signature = self.signature.iter().flat_map(|value| value.to_le_bytes().to_vec()).collect::<Vec<_>>();
+
+        code.windows(signature.len()).position(|window| window == signature.as_slice())
+    }
+
+    pub fn read(&self, code: &[u8]) -> Result<Vec<u16>, Error> {
+        let offset = self.locate(code)?;
+
+        Ok(code[offset..offset + 2 * self.count].chunks(2).map(|value| u16::from_le_bytes([value[0], value[1]])).collect())
+    }
+
+    // Values are written in place, so edits have to keep the first entries
+    // intact for the table to be found again.
+    pub fn write(&self, code: &mut [u8], values: &[u16]) -> Result<(), Error> {
+        if values.len() != self.count {
+            return Err(Error::malformed(format!("expected {} values, got {}", self.count, values.len())));
+        }
+
+        let offset = self.locate(code)?;
+        for (i, value) in values.iter().enumerate() {
+            code[offset + 2 * i..offset + 2 * i + 2].copy_from_slice(&value.to_le_bytes());
+        }
+
+        Ok(())
+    }
+
+    fn locate(&self, code: &[u8]) -> Result<usize, Error> {
+        match self.find(code) {
+            Some(offset) if offset + 2 * self.count <= code.len() => Ok(offset),
+            _ => Err(Error::not_found("table in code binary")),
+        }
+    }
+}
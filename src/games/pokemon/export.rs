@@ -0,0 +1,140 @@
+use super::super::super::error::Error;
+use std::io::Write;
+use super::super::super::read::VirtualFile;
+use super::super::super::pokemon::text::Texts;
+use super::Game;
+use super::Language;
+use super::Pokemon;
+
+// Common text has names, menus and descriptions, story text has one file per
+// map or script. Both are an archive per language, see `files::Files`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextKind {
+    Common,
+    Story,
+}
+
+impl TextKind {
+    pub fn archive(&self, game: Game, language: Language) -> Result<String, Error> {
+        let files = game.files();
+        match self {
+            Self::Common => files.common_text(language),
+            Self::Story => files.story_text(language),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Common => "common",
+            Self::Story => "story",
+        }
+    }
+}
+
+// There's no speaker: it's set by the script showing the line, not stored in
+// the text file.
+#[derive(Debug, Clone)]
+pub struct ExportedLine {
+    pub kind: TextKind,
+    pub file: usize,
+    pub section: usize,
+    pub line: usize,
+    pub flags: u16,
+    pub text: String,
+}
+
+impl<'a> Pokemon<'a> {
+    pub fn export_texts(&self, language: Language) -> Result<Vec<ExportedLine>, Error> {
+        let mut lines = vec![];
+
+        for kind in [TextKind::Common, TextKind::Story].iter() {
+            let garc = self.garc(&kind.archive(self.product, language)?)?;
+
+            let mut it = garc.entries();
+            while let Some(entry) = it.try_next()? {
+                let subentry = match entry.entries().try_next()? {
+                    Some(subentry) => subentry,
+                    None => continue,
+                };
+
+                let texts = Texts::new(subentry.reader()?)?;
+                for section in 0..texts.section_count() as usize {
+                    for (index, line) in texts.section(section)?.lines().enumerate() {
+                        let line = line?;
+
+                        lines.push(ExportedLine {
+                            kind: *kind,
+                            file: entry.index() as usize,
+                            section,
+                            line: index,
+                            flags: line.flags,
+                            text: line.text,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(lines)
+    }
+}
+
+pub fn write_csv(lines: &[ExportedLine], output: &mut dyn Write) -> Result<(), Error> {
+    writeln!(output, "kind,file,section,line,flags,text")?;
+
+    for line in lines {
+        writeln!(
+            output,
+            "{},{},{},{},{},\"{}\"",
+            line.kind.name(),
+            line.file,
+            line.section,
+            line.line,
+            line.flags,
+            line.text.replace('"', "\"\""),
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn write_json(lines: &[ExportedLine], output: &mut dyn Write) -> Result<(), Error> {
+    writeln!(output, "[")?;
+
+    for (i, line) in lines.iter().enumerate() {
+        writeln!(
+            output,
+            "  {{\"kind\": \"{}\", \"file\": {}, \"section\": {}, \"line\": {}, \"flags\": {}, \"text\": {}}}{}",
+            line.kind.name(),
+            line.file,
+            line.section,
+            line.line,
+            line.flags,
+            json_string(&line.text),
+            if i + 1 < lines.len() { "," } else { "" },
+        )?;
+    }
+
+    writeln!(output, "]")?;
+
+    Ok(())
+}
+
+fn json_string(text: &str) -> String {
+    let mut output = String::from("\"");
+
+    for c in text.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if (c as u32) < 0x20 => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+
+    output.push('"');
+    output
+}
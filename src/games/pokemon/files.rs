@@ -0,0 +1,129 @@
+use super::super::super::error::Error;
+use super::Game;
+use super::Language;
+
+// Where each kind of data lives in the RomFS. Numbers are GARC indices, so
+// 72 is a/0/7/2. Text archives are one per language, starting at the given
+// index, for the first `languages` values of Language. Files that don't exist
+// in a generation are None.
+#[derive(Debug, Clone, Copy)]
+pub struct Files {
+    pub common_text: usize,
+    pub story_text: usize,
+    pub languages: usize,
+    pub personal: usize,
+    pub personal_size: usize,
+    pub learnsets: usize,
+    pub moves: usize,
+    // Sun/Moon and OR/AS pack every move in a single WD mini, X/Y has one
+    // GARC entry per move.
+    pub moves_packed: bool,
+    pub items: usize,
+    pub form_links: Option<usize>,
+    pub zones: Option<usize>,
+    pub wild_encounters: Option<usize>,
+    pub world_data: Option<usize>,
+    pub trainer_data: Option<usize>,
+    pub trainer_teams: Option<usize>,
+    pub static_encounters: Option<usize>,
+}
+
+const XY: Files = Files {
+    common_text: 70,
+    story_text: 78,
+    languages: 8,
+    personal: 218,
+    personal_size: 0x40,
+    learnsets: 214,
+    moves: 212,
+    moves_packed: false,
+    items: 220,
+    form_links: None,
+    zones: None,
+    wild_encounters: None,
+    world_data: None,
+    trainer_data: None,
+    trainer_teams: None,
+    static_encounters: None,
+};
+
+const ORAS: Files = Files {
+    common_text: 69,
+    story_text: 77,
+    languages: 8,
+    personal: 195,
+    personal_size: 0x50,
+    learnsets: 191,
+    moves: 189,
+    moves_packed: true,
+    items: 199,
+    form_links: None,
+    zones: None,
+    wild_encounters: None,
+    world_data: None,
+    trainer_data: None,
+    trainer_teams: None,
+    static_encounters: None,
+};
+
+const SUN_MOON: Files = Files {
+    common_text: 30,
+    story_text: 40,
+    languages: 10,
+    personal: 17,
+    personal_size: 0x54,
+    learnsets: 13,
+    moves: 11,
+    moves_packed: true,
+    items: 19,
+    form_links: Some(152),
+    zones: Some(77),
+    wild_encounters: Some(82),
+    world_data: Some(91),
+    trainer_data: Some(105),
+    trainer_teams: Some(106),
+    static_encounters: Some(155),
+};
+
+const ULTRA_SUN_MOON: Files = Files {
+    form_links: Some(156),
+    trainer_data: Some(106),
+    trainer_teams: Some(107),
+    static_encounters: Some(159),
+    ..SUN_MOON
+};
+
+impl Files {
+    pub fn common_text(&self, language: Language) -> Result<String, Error> {
+        self.text(self.common_text, language)
+    }
+
+    pub fn story_text(&self, language: Language) -> Result<String, Error> {
+        self.text(self.story_text, language)
+    }
+
+    // Chinese was only added in Sun/Moon, past the end the index would land
+    // in the next group of archives.
+    fn text(&self, first: usize, language: Language) -> Result<String, Error> {
+        if language as usize >= self.languages {
+            return Err(Error::unsupported(format!("{:?} text in this game", language)));
+        }
+
+        Ok(path(first + language as usize))
+    }
+}
+
+impl Game {
+    pub fn files(&self) -> &'static Files {
+        match self {
+            Game::X | Game::Y => &XY,
+            Game::OmegaRuby | Game::AlphaSapphire => &ORAS,
+            Game::Sun | Game::Moon => &SUN_MOON,
+            Game::UltraSun | Game::UltraMoon => &ULTRA_SUN_MOON,
+        }
+    }
+}
+
+pub fn path(garc: usize) -> String {
+    format!("a/{}/{}/{}", garc / 100, garc / 10 % 10, garc % 10)
+}
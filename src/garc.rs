@@ -1,9 +1,9 @@
 use byteorder::LittleEndian;
 use byteorder::ReadBytesExt;
-use std::io::Error;
+use super::error::Error;
+use std::convert::TryFrom;
 use std::io::Read;
 use std::io::Seek;
-use std::io::SeekFrom;
 use super::read::Reader;
 use super::read::VirtualFile;
 
@@ -13,8 +13,10 @@ pub struct GARC<'a> {
     header: Header,
 }
 
+pub type OwnedGARC = GARC<'static>;
+
 impl<'a> GARC<'a> {
-    pub fn new(mut file: Reader<'a>) -> Result<GARC, std::io::Error> {
+    pub fn new(mut file: Reader<'a>) -> Result<GARC, Error> {
         let header = Header::read(&mut file)?;
 
         Ok(GARC { file, header })
@@ -24,44 +26,52 @@ impl<'a> GARC<'a> {
         self.header.otaf_file_count
     }
 
+    pub fn len(&self) -> usize {
+        self.header.otaf_file_count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn context<'b>(&'b self) -> FileIteratorContext<'a, 'b> {
+        FileIteratorContext {
+            file: self.file.clone(),
+            file_count: self.header.otaf_file_count,
+            base_offset: self.header.btaf_offset,
+            fat_offsets: &self.header.otaf_entries,
+            data_offset: self.header.data_offset as u64,
+        }
+    }
+
     pub fn entries<'b>(&'b self) -> FileIterator<'a, 'b> {
         FileIterator {
-            context: FileIteratorContext {
-                file: self.file.clone(),
-                file_count: self.header.otaf_file_count,
-                base_offset: self.header.btaf_offset,
-                fat_offsets: &self.header.otaf_entries,
-                data_offset: self.header.data_offset as u64,
-            },
+            context: self.context(),
             index: 0,
         }
     }
 
-    pub fn file_at(&self, i: usize, j: usize) -> Result<Option<SubfileEntry<'a>>, std::io::Error> {
-        let mut index = 0;
-        let mut it = self.entries();
-        while let Some(entry) = it.try_next()? {
-            if index == i {
-                let mut subindex = 0;
-                let mut jt = entry.entries();
-                while let Some(subentry) = jt.try_next()? {
-                    if subindex == j {
-                        return Ok(Some(subentry));
-                    }
-                    subindex += 1;
-                }
-            }
-
-            index += 1;
+    // FATO has every entry's offset, so this doesn't read the ones before it.
+    pub fn entry<'b>(&'b self, i: usize) -> Result<Option<FileEntry<'a, 'b>>, Error> {
+        match u16::try_from(i) {
+            Ok(index) if index < self.header.otaf_file_count => self.context().entry(index).map(Some),
+            _ => Ok(None),
         }
+    }
 
-        Ok(None)
+    // j counts the subentries that exist, so it's the same as the subentry's
+    // index only when the entry has no gaps. See FileEntry::subfile.
+    pub fn file_at(&self, i: usize, j: usize) -> Result<Option<SubfileEntry<'a>>, Error> {
+        match self.entry(i)? {
+            Some(entry) => entry.nth_subfile(j),
+            None => Ok(None),
+        }
     }
 }
 
 impl<'a> VirtualFile<'a> for GARC<'a> {
-    fn reader(&self) -> Reader<'a> {
-        self.file.at_zero()
+    fn reader(&self) -> Result<Reader<'a>, Error> {
+        Ok(self.file.at_zero())
     }
 }
 
@@ -98,18 +108,22 @@ pub struct Header {
 }
 
 impl Header {
-    pub fn read(input: &mut Reader) -> Result<Self, std::io::Error> {
+    pub fn read(input: &mut Reader) -> Result<Self, Error> {
         let mut buf = vec![];
         let mut header = Header::default();
 
         header.magic         = input.read_u32::<LittleEndian>()?;
+        if header.magic != u32::from_le_bytes(*b"CRAG") {
+            return Err(Error::bad_magic(b"CRAG", &header.magic.to_le_bytes(), 0));
+        }
+
         header.header_length = input.read_u32::<LittleEndian>()?;
         header.endianess     = input.read_u16::<LittleEndian>()?;
 
         header.version   = input.read_u16::<LittleEndian>()?;
 
         if !(header.version == 0x400 || header.version == 0x600) {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "wrong GARC version"));
+            return Err(Error::UnsupportedVersion { what: "GARC", version: header.version as u64 });
         }
 
         header.file_size = input.read_u32::<LittleEndian>()?;
@@ -127,41 +141,64 @@ impl Header {
             header.content_pad_to_nearest = input.read_u32::<LittleEndian>()?;
         }
 
+        let offset = input.stream_position()?;
         header.otaf_magic = input.read_u32::<LittleEndian>()?;
-        assert!(header.otaf_magic == 1178686543);
+        if header.otaf_magic != u32::from_le_bytes(*b"OTAF") {
+            return Err(Error::bad_magic(b"OTAF", &header.otaf_magic.to_le_bytes(), offset));
+        }
         header.otaf_section_size = input.read_u32::<LittleEndian>()?;
         header.otaf_file_count = input.read_u16::<LittleEndian>()?;
         header.otaf_padding = input.read_u16::<LittleEndian>()?;
 
-        assert!(header.otaf_section_size - 12 == header.otaf_file_count as u32 * 4);
+        if header.otaf_section_size.checked_sub(12) != Some(header.otaf_file_count as u32 * 4) {
+            return Err(Error::malformed("FATO section size doesn't match its file count"));
+        }
 
         for _ in 0..header.otaf_file_count {
             let v = input.read_u32::<LittleEndian>()?;
             header.otaf_entries.push(v);
         }
 
+        let offset = input.stream_position()?;
         header.btaf_magic = input.read_u32::<LittleEndian>()?;
-        assert!(header.btaf_magic == 1178686530);
+        if header.btaf_magic != u32::from_le_bytes(*b"BTAF") {
+            return Err(Error::bad_magic(b"BTAF", &header.btaf_magic.to_le_bytes(), offset));
+        }
         header.btaf_section_size = input.read_u32::<LittleEndian>()?;
         header.btaf_file_count = input.read_u32::<LittleEndian>()?;
         header.btaf_offset = input.stream_position()?;
 
-        if !(header.btaf_section_size - 12 == header.btaf_file_count as u32 * 16) {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "btaf derp"));
+        let fatb_length = header.btaf_section_size.checked_sub(12).ok_or_else(|| Error::malformed("FATB section is smaller than its header"))?;
+        if Some(fatb_length) != header.btaf_file_count.checked_mul(16) {
+            return Err(Error::malformed("FATB section size doesn't match its file count"));
         }
-        buf.resize(header.btaf_section_size as usize - 12, 0);
+        if fatb_length as u64 > input.length() {
+            return Err(Error::out_of_bounds("FATB", header.btaf_offset, fatb_length as u64, input.length()));
+        }
+        buf.resize(fatb_length as usize, 0);
         input.read_exact(&mut buf)?;
 
 
+        let offset = input.stream_position()?;
         header.bmif_magic = input.read_u32::<LittleEndian>()?;
+        if header.bmif_magic != u32::from_le_bytes(*b"BMIF") {
+            return Err(Error::bad_magic(b"BMIF", &header.bmif_magic.to_le_bytes(), offset));
+        }
         header.bmif_section_size = input.read_u32::<LittleEndian>()?;
         header.bmif_data_size = input.read_u32::<LittleEndian>()?;
 
-        buf.resize(header.bmif_section_size as usize - 12, 0);
+        if header.bmif_section_size as u64 > input.length() {
+            return Err(Error::out_of_bounds("FIMB", offset, header.bmif_section_size as u64, input.length()));
+        }
+        buf.resize(header.bmif_section_size.saturating_sub(12) as usize, 0);
         input.read_exact(&mut buf)?;
 
-        assert!(header.data_offset as u64 == input.stream_position().unwrap());
-        assert!(header.data_offset + header.bmif_data_size == header.file_length);
+        if header.data_offset as u64 != input.stream_position()? {
+            return Err(Error::malformed("GARC data doesn't start right after FIMB"));
+        }
+        if header.data_offset as u64 + header.bmif_data_size as u64 != header.file_length as u64 {
+            return Err(Error::malformed("GARC length doesn't match its data size"));
+        }
 
         Ok(header)
     }
@@ -176,6 +213,22 @@ pub struct FileIteratorContext<'a, 'b> {
     fat_offsets: &'b [u32],
 }
 
+impl<'a, 'b> FileIteratorContext<'a, 'b> {
+    fn file_offset(&self, index: u16) -> u64 {
+        self.base_offset + self.fat_offsets[index as usize] as u64
+    }
+
+    fn entry(&self, index: u16) -> Result<FileEntry<'a, 'b>, Error> {
+        let header = FileEntryHeader::read(&mut self.file.at(self.file_offset(index))?)?;
+
+        Ok(FileEntry {
+            context: self.clone(),
+            index,
+            header,
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct FileIterator<'a, 'b> {
     context: FileIteratorContext<'a, 'b>,
@@ -183,30 +236,29 @@ pub struct FileIterator<'a, 'b> {
 }
 
 impl<'a, 'b> Iterator for FileIterator<'a, 'b> {
-    type Item = Result<FileEntry<'a, 'b>, std::io::Error>;
+    type Item = Result<FileEntry<'a, 'b>, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.try_next().transpose()
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.context.file_count.saturating_sub(self.index) as usize;
+        (remaining, Some(remaining))
+    }
 }
 
 impl<'a, 'b> FileIterator<'a, 'b> {
     pub fn file_offset(&self) -> u64 {
-        self.context.base_offset + self.context.fat_offsets[self.index as usize] as u64
+        self.context.file_offset(self.index)
     }
 
-    pub fn try_next(&mut self) -> Result<Option<FileEntry<'a, 'b>>, std::io::Error> {
+    pub fn try_next(&mut self) -> Result<Option<FileEntry<'a, 'b>>, Error> {
         if self.index < self.context.file_count {
-            self.context.file.seek(SeekFrom::Start(self.file_offset()))?;
+            let entry = self.context.entry(self.index)?;
             self.index += 1;
 
-            let header = FileEntryHeader::read(&mut self.context.file)?;
-
-            Ok(Some(FileEntry {
-                context: self.context.clone(),
-                index: self.index - 1,
-                header,
-            }))
+            Ok(Some(entry))
         } else {
             Ok(None)
         }
@@ -226,13 +278,60 @@ impl<'a, 'b> FileEntry<'a, 'b> {
             context: self.context.clone(),
             index: 0,
             vector: self.header.vector,
-            offset: self.context.base_offset + self.context.fat_offsets[self.index as usize] as u64 + 4,
+            offset: self.subfiles_offset(),
         }
     }
 
     pub fn index(&self) -> u16 {
         self.index
     }
+
+    // One bit per possible subentry, and only the set ones are stored.
+    pub fn subfile_count(&self) -> u32 {
+        self.header.vector.count_ones()
+    }
+
+    // Subentries are stored in bit order, so the bits below j say where it is.
+    pub fn subfile(&self, j: usize) -> Result<Option<SubfileEntry<'a>>, Error> {
+        if j >= 32 || self.header.vector & (1 << j) == 0 {
+            return Ok(None);
+        }
+
+        let position = (self.header.vector & ((1 << j) - 1)).count_ones() as u64;
+        let offset = self.subfiles_offset() + position * SubfileEntryHeader::SIZE;
+
+        read_subfile(&self.context, offset, j as u8).map(Some)
+    }
+
+    // The nth subentry that exists, whatever its index.
+    pub fn nth_subfile(&self, n: usize) -> Result<Option<SubfileEntry<'a>>, Error> {
+        if n >= self.subfile_count() as usize {
+            return Ok(None);
+        }
+
+        // Drop the n lowest set bits, the next one is the subentry's index.
+        let vector = (0..n).fold(self.header.vector, |vector, _| vector & (vector - 1));
+        let offset = self.subfiles_offset() + n as u64 * SubfileEntryHeader::SIZE;
+
+        read_subfile(&self.context, offset, vector.trailing_zeros() as u8).map(Some)
+    }
+
+    fn subfiles_offset(&self) -> u64 {
+        self.context.file_offset(self.index) + 4
+    }
+}
+
+fn read_subfile<'a>(context: &FileIteratorContext<'a, '_>, offset: u64, index: u8) -> Result<SubfileEntry<'a>, Error> {
+    let header = SubfileEntryHeader::read(&mut context.file.at(offset)?)?;
+
+    Ok(SubfileEntry {
+        context: SubfileEntryContext {
+            file: context.file.clone(),
+            data_offset: context.data_offset,
+        },
+        header,
+        index,
+    })
 }
 
 #[derive(Debug)]
@@ -243,30 +342,37 @@ pub struct SubfileIterator<'a, 'b> {
     offset: u64,
 }
 
-impl<'a, 'b> SubfileIterator<'a, 'b> {
-    pub fn try_next(&mut self) -> Result<Option<SubfileEntry<'a>>, std::io::Error> {
-        if self.vector >> self.index == 0 {
-            Ok(None)
-        } else {
-            while (self.vector & (1u32 << self.index)) == 0 {
-                self.index += 1;
-            }
+impl<'a, 'b> Iterator for SubfileIterator<'a, 'b> {
+    type Item = Result<SubfileEntry<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next().transpose()
+    }
 
-            self.context.file.seek(SeekFrom::Start(self.offset))?;
-            let header = SubfileEntryHeader::read(&mut self.context.file)?;
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining().count_ones() as usize;
+        (remaining, Some(remaining))
+    }
+}
 
-            self.index += 1;
-            self.offset = self.context.file.stream_position()?;
-
-            Ok(Some(SubfileEntry {
-                context: SubfileEntryContext {
-                    file: self.context.file.clone(),
-                    data_offset: self.context.data_offset,
-                },
-                header,
-                index: self.index - 1,
-            }))
+impl<'a, 'b> SubfileIterator<'a, 'b> {
+    fn remaining(&self) -> u32 {
+        self.vector.checked_shr(self.index as u32).unwrap_or(0)
+    }
+
+    pub fn try_next(&mut self) -> Result<Option<SubfileEntry<'a>>, Error> {
+        let remaining = self.remaining();
+        if remaining == 0 {
+            return Ok(None);
         }
+
+        let index = self.index + remaining.trailing_zeros() as u8;
+        let subentry = read_subfile(&self.context, self.offset, index)?;
+
+        self.index = index + 1;
+        self.offset += SubfileEntryHeader::SIZE;
+
+        Ok(Some(subentry))
     }
 }
 
@@ -305,11 +411,11 @@ impl<'a> SubfileEntry<'a> {
 }
 
 impl<'a> VirtualFile<'a> for SubfileEntry<'a> {
-    fn reader(&self) -> Reader<'a> {
+    fn reader(&self) -> Result<Reader<'a>, Error> {
         self.context.file.limit(
             self.context.data_offset + self.header.start as u64,
             self.header.length as u64,
-        ).unwrap()
+        )
     }
 }
 
@@ -321,6 +427,8 @@ pub struct SubfileEntryHeader {
 }
 
 impl SubfileEntryHeader {
+    const SIZE: u64 = 12;
+
     pub fn read(input: &mut Reader) -> Result<Self, Error> {
         let mut subentry = Self::default();
 
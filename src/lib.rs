@@ -1,13 +1,21 @@
 //pub mod bclim;
 //pub mod darc;
 //pub mod lzss;
+pub mod blz;
 pub mod romfs;
+pub mod overlay;
 pub mod garc;
 pub mod pokemon;
 
+pub mod error;
 pub mod read;
+pub mod mmap;
 
 pub mod ncsd;
 pub mod ncch;
+pub mod exefs;
+pub mod cia;
+
+pub mod extract;
 
 pub mod games;
@@ -0,0 +1,88 @@
+// Read-only memory map of a whole file. Pages are loaded by the OS as they're
+// touched, so even a 3.5 GB image costs nothing until it's read.
+
+#[cfg(unix)]
+pub struct Mmap {
+    pointer: *mut libc::c_void,
+    length: usize,
+}
+
+// The mapping is read-only and never remapped, so sharing it is fine.
+#[cfg(unix)]
+unsafe impl Send for Mmap {}
+#[cfg(unix)]
+unsafe impl Sync for Mmap {}
+
+#[cfg(unix)]
+impl Mmap {
+    pub fn new(file: &std::fs::File) -> Result<Mmap, std::io::Error> {
+        use std::os::unix::io::AsRawFd;
+
+        let length = file.metadata()?.len() as usize;
+
+        // mmap refuses empty mappings.
+        if length == 0 {
+            return Ok(Mmap { pointer: std::ptr::null_mut(), length });
+        }
+
+        let pointer = unsafe {
+            libc::mmap(std::ptr::null_mut(), length, libc::PROT_READ, libc::MAP_PRIVATE, file.as_raw_fd(), 0)
+        };
+
+        if pointer == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        Ok(Mmap { pointer, length })
+    }
+}
+
+#[cfg(unix)]
+impl std::ops::Deref for Mmap {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        if self.length == 0 {
+            return &[];
+        }
+
+        unsafe { std::slice::from_raw_parts(self.pointer as *const u8, self.length) }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.length > 0 {
+            unsafe { libc::munmap(self.pointer, self.length); }
+        }
+    }
+}
+
+// Without mmap the file is read into memory instead, which gives the same
+// zero-copy slices at the cost of loading it all up front.
+#[cfg(not(unix))]
+pub struct Mmap {
+    bytes: Vec<u8>,
+}
+
+#[cfg(not(unix))]
+impl Mmap {
+    pub fn new(mut file: &std::fs::File) -> Result<Mmap, std::io::Error> {
+        use std::io::Read;
+
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes)?;
+
+        Ok(Mmap { bytes })
+    }
+}
+
+#[cfg(not(unix))]
+impl std::ops::Deref for Mmap {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
@@ -1,4 +1,6 @@
+use super::error::Error;
 use super::romfs::RomFS;
+use super::exefs::ExeFS;
 use super::read::Reader;
 use super::read::VirtualFile;
 use std::io::Read;
@@ -11,14 +13,16 @@ pub struct NCCH<'a> {
     header: Header,
 }
 
+pub type OwnedNCCH = NCCH<'static>;
+
 impl<'a> NCCH<'a> {
-    pub fn new(mut file: Reader<'a>) -> Result<NCCH, std::io::Error> {
+    pub fn new(mut file: Reader<'a>) -> Result<NCCH, Error> {
         let header = Header::read(&mut file)?;
 
         Ok(NCCH { file, header })
     }
 
-    pub fn romfs(&self) -> Result<Option<RomFS<'a>>, std::io::Error> {
+    pub fn romfs(&self) -> Result<Option<RomFS<'a>>, Error> {
         let rom = RomFS::new(self.file.limit(self.header.romfs_offset, self.header.romfs_size)?)?;
 
         Ok(Some(rom))
@@ -28,12 +32,23 @@ impl<'a> NCCH<'a> {
         self.header.partition_id
     }
 
-    pub fn product_code(&self) -> Result<String, std::str::Utf8Error> {
-        std::str::from_utf8(&self.header.product_code).map(|s| s.trim_end_matches('\x00').into())
+    pub fn program_id(&self) -> u64 {
+        self.header.program_id
+    }
+
+    // title version from the extended header, not the NCCH format version.
+    pub fn remaster_version(&self) -> Result<u16, Error> {
+        Ok(self.file.limit(0x200 + 0xE, 2)?.read_u16::<LittleEndian>()?)
+    }
+
+    pub fn product_code(&self) -> Result<String, Error> {
+        std::str::from_utf8(&self.header.product_code)
+            .map(|s| s.trim_end_matches('\x00').into())
+            .map_err(|_| Error::malformed(format!("product code isn't text: {:02x?}", self.header.product_code)))
     }
 
     // plain region not yet implemented
-    pub fn plain_region(&'a self) -> Result<Option<Reader<'a>>, std::io::Error> {
+    pub fn plain_region(&self) -> Result<Option<Reader<'a>>, Error> {
         if self.header.plain_region_offset == 0 {
             Ok(None)
         } else {
@@ -41,17 +56,43 @@ impl<'a> NCCH<'a> {
         }
     }
 
-    // exefs region not yet implemented
-    pub fn exefs(&'a self) -> Result<Option<Reader<'a>>, std::io::Error> {
+    pub fn exefs(&self) -> Result<Option<ExeFS<'a>>, Error> {
         if self.header.exefs_offset == 0 {
             Ok(None)
         } else {
-            Ok(Some(self.file.limit(self.header.exefs_offset, self.header.exefs_size)?))
+            Ok(Some(ExeFS::new(self.file.limit(self.header.exefs_offset, self.header.exefs_size)?)?))
         }
     }
 
+    // .code from ExeFS, decompressed if the extended header says so.
+    pub fn code(&self) -> Result<Option<Vec<u8>>, Error> {
+        let mut code = match self.exefs()? {
+            Some(exefs) => match exefs.file_at(".code")? {
+                Some(code) => code,
+                None => { return Ok(None); },
+            },
+            None => { return Ok(None); },
+        };
+
+        let mut buffer = vec![];
+        code.read_to_end(&mut buffer)?;
+
+        if self.code_compressed()? {
+            Ok(Some(super::blz::decompress(&buffer)?))
+        } else {
+            Ok(Some(buffer))
+        }
+    }
+
+    fn code_compressed(&self) -> Result<bool, Error> {
+        // system control info flags, first thing in the extended header.
+        let mut exheader = self.file.limit(0x200 + 0xD, 1)?;
+
+        Ok(exheader.read_u8()? & 1 == 1)
+    }
+
     // logo region not yet implemented
-    pub fn logo(&'a self) -> Result<Option<Reader<'a>>, std::io::Error> {
+    pub fn logo(&self) -> Result<Option<Reader<'a>>, Error> {
         if self.header.logo_region_offset == 0 {
             Ok(None)
         } else {
@@ -61,8 +102,8 @@ impl<'a> NCCH<'a> {
 }
 
 impl<'a> VirtualFile<'a> for NCCH<'a> {
-    fn reader(&self) -> Reader<'a> {
-        self.file.at_zero()
+    fn reader(&self) -> Result<Reader<'a>, Error> {
+        Ok(self.file.at_zero())
     }
 }
 
@@ -100,14 +141,16 @@ struct Header {
 }
 
 impl Header {
-    fn read(input: &mut Reader) -> Result<Header, std::io::Error> {
+    fn read(input: &mut Reader) -> Result<Header, Error> {
         let mut header = Header::default();
         header.signature.resize(0x100, 0);
 
         input.read_exact(&mut header.signature)?;
         input.read_exact(&mut header.magic)?;
 
-        assert!(header.magic == *b"NCCH");
+        if header.magic != *b"NCCH" {
+            return Err(Error::bad_magic(b"NCCH", &header.magic, 0x100));
+        }
 
         header.size = input.read_u32::<LittleEndian>()? as u64 * 0x200;
         header.partition_id = input.read_u64::<LittleEndian>()?;
@@ -1,23 +1,18 @@
+use super::error::Error;
 use super::ncch::NCCH;
 use super::read::Reader;
 use std::io::Read;
 use byteorder::ReadBytesExt;
 use byteorder::LittleEndian;
 
-fn expect<F>(good: bool, f: F) -> Result<(), std::io::Error> where F: Fn() -> String {
-    if good {
-        Ok(())
-    } else {
-        Err(std::io::Error::new(std::io::ErrorKind::Other, f()))
-    }
-}
-
 #[derive(Debug)]
 pub struct NCSD<'a> {
     file: Reader<'a>,
     header: Header,
 }
 
+pub type OwnedNCSD = NCSD<'static>;
+
 pub enum Partition {
     Main,
     Manual,
@@ -28,13 +23,13 @@ pub enum Partition {
 }
 
 impl<'a> NCSD<'a> {
-    pub fn new(mut file: Reader<'a>) -> Result<NCSD, std::io::Error> {
+    pub fn new(mut file: Reader<'a>) -> Result<NCSD, Error> {
         let header = Header::read(&mut file)?;
 
         Ok(NCSD { file, header })
     }
 
-    pub fn partition(&self, p: Partition) -> Result<NCCH<'a>, std::io::Error> {
+    pub fn partition(&self, p: Partition) -> Result<NCCH<'a>, Error> {
         match p {
             Partition::Main => self.partition(Partition::Index(0)),
             Partition::Manual => self.partition(Partition::Index(1)),
@@ -43,11 +38,11 @@ impl<'a> NCSD<'a> {
             Partition::UpdateData => self.partition(Partition::Index(7)),
             Partition::Index(index) => {
                 if index >= 8 {
-                    return Err(std::io::Error::new(std::io::ErrorKind::Other, "Partition number out of bounds"));
+                    return Err(Error::out_of_bounds("partition", index as u64, 1, 8));
                 }
 
                 if self.header.partition_offset(index) == 0 {
-                    return Err(std::io::Error::new(std::io::ErrorKind::Other, "Partition is empty"));
+                    return Err(Error::not_found(&format!("partition {}", index)));
                 }
 
                 NCCH::new(self.file.limit(self.header.partition_offset(index), self.header.partition_length(index))?)
@@ -72,12 +67,13 @@ pub struct PartitionIterator<'a> {
 }
 
 impl<'a> PartitionIterator<'a> {
-    pub fn next(&mut self) -> Result<Option<NCCH>, std::io::Error> {
-        if self.index < 8 {
-            while self.header.partition_offsets[self.index] == 0 {
-                self.index += 1;
-            }
+    pub fn next(&mut self) -> Result<Option<NCCH<'a>>, Error> {
+        // Unused partitions, including trailing ones, have a zero offset.
+        while self.index < 8 && self.header.partition_offsets[self.index] == 0 {
+            self.index += 1;
+        }
 
+        if self.index < 8 {
             let partition = NCCH::new(self.file.limit(self.header.partition_offset(self.index), self.header.partition_length(self.index))?).map(Option::Some);
 
             self.index += 1;
@@ -121,14 +117,16 @@ impl Header {
     }
 
 
-    fn read(input: &mut Reader) -> Result<Header, std::io::Error> {
+    fn read(input: &mut Reader) -> Result<Header, Error> {
         let mut header = Header::default();
         header.signature.resize(0x100, 0);
 
         input.read_exact(&mut header.signature)?;
         input.read_exact(&mut header.magic)?;
 
-        expect(header.magic == *b"NCSD", || format!("magic number not NCSD: {:?}", header.magic))?;
+        if header.magic != *b"NCSD" {
+            return Err(Error::bad_magic(b"NCSD", &header.magic, 0x100));
+        }
 
         header.size = input.read_u32::<LittleEndian>()? as u64 * 0x200;
 
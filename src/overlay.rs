@@ -0,0 +1,48 @@
+use super::error::Error;
+use super::ncch::NCCH;
+use super::romfs::Node;
+use super::romfs::RomFS;
+
+// A game's RomFS with an update's RomFS on top. Updates ship whole files, so
+// a path found in the update replaces the base game's one.
+#[derive(Debug)]
+pub struct Overlay<'a> {
+    base: RomFS<'a>,
+    update: Option<RomFS<'a>>,
+}
+
+pub type OwnedOverlay = Overlay<'static>;
+
+impl<'a> Overlay<'a> {
+    pub fn new(base: &NCCH<'a>, update: Option<&NCCH<'a>>) -> Result<Overlay<'a>, Error> {
+        let base = base.romfs()?.ok_or_else(|| Error::not_found("base game RomFS"))?;
+        let update = match update {
+            Some(update) => update.romfs()?,
+            None => None,
+        };
+
+        Ok(Overlay { base, update })
+    }
+
+    pub fn base(&self) -> &RomFS<'a> {
+        &self.base
+    }
+
+    pub fn update(&self) -> Option<&RomFS<'a>> {
+        self.update.as_ref()
+    }
+
+    pub fn file_at(&self, path: &str) -> Result<Option<Node<'a>>, Error> {
+        if let Some(update) = &self.update {
+            if let Some(node) = update.file_at(path)? {
+                return Ok(Some(node));
+            }
+        }
+
+        self.base.file_at(path)
+    }
+
+    pub fn exists(&self, path: &str) -> Result<bool, Error> {
+        Ok(self.file_at(path)?.is_some())
+    }
+}
@@ -1,2 +1,10 @@
 pub mod text;
 pub mod table;
+pub mod mini;
+pub mod moves;
+pub mod items;
+pub mod encounters;
+pub mod trainers;
+pub mod static_encounters;
+pub mod personal;
+pub mod learnsets;
@@ -0,0 +1,147 @@
+use super::super::error::Error;
+use byteorder::LittleEndian;
+use byteorder::ReadBytesExt;
+use byteorder::WriteBytesExt;
+use std::io::Read;
+use std::io::Write;
+use super::table::Record;
+
+// species in the low 11 bits, form in the rest.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Slot {
+    pub species: u16,
+    pub form: u8,
+}
+
+impl Slot {
+    fn from_raw(raw: u32) -> Self {
+        Slot {
+            species: (raw & 0x7FF) as u16,
+            form: (raw >> 11) as u8,
+        }
+    }
+
+    fn raw(&self) -> u32 {
+        (self.species as u32 & 0x7FF) | ((self.form as u32) << 11)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.species == 0
+    }
+}
+
+impl Record for Slot {
+    const SIZE: usize = 4;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error> {
+        Ok(Self::from_raw(input.read_u32::<LittleEndian>()?))
+    }
+
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        Ok(output.write_u32::<LittleEndian>(self.raw())?)
+    }
+}
+
+// One table of ten slots. The first row holds the regular encounters, the
+// other seven the SOS allies that can be called by each of them. Weather
+// specific allies are kept apart.
+#[derive(Debug, Default, Clone)]
+pub struct EncounterTable {
+    pub min_level: u8,
+    pub max_level: u8,
+    pub rates: [u8; 10],
+    pub slots: [Slot; 10],
+    pub sos_slots: [[Slot; 10]; 7],
+    pub weather_sos_slots: [Slot; 6],
+}
+
+impl EncounterTable {
+    pub fn is_empty(&self) -> bool {
+        self.slots.iter().all(Slot::is_empty)
+    }
+}
+
+impl Record for EncounterTable {
+    const SIZE: usize = 0x164;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error> {
+        let mut table = Self::default();
+
+        table.min_level = input.read_u8()?;
+        table.max_level = input.read_u8()?;
+        input.read_exact(&mut table.rates)?;
+
+        for slot in table.slots.iter_mut() {
+            *slot = Slot::read(input)?;
+        }
+        for row in table.sos_slots.iter_mut() {
+            for slot in row.iter_mut() {
+                *slot = Slot::read(input)?;
+            }
+        }
+        for slot in table.weather_sos_slots.iter_mut() {
+            *slot = Slot::read(input)?;
+        }
+
+        Ok(table)
+    }
+
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        output.write_u8(self.min_level)?;
+        output.write_u8(self.max_level)?;
+        output.write_all(&self.rates)?;
+
+        for slot in self.slots.iter() {
+            slot.write(output)?;
+        }
+        for row in self.sos_slots.iter() {
+            for slot in row.iter() {
+                slot.write(output)?;
+            }
+        }
+        for slot in self.weather_sos_slots.iter() {
+            slot.write(output)?;
+        }
+
+        Ok(())
+    }
+}
+
+// One 0x54 byte entry of the zone data file. Only the fields needed to name
+// a zone are decoded. The bytes stay private so there are always SIZE of them.
+#[derive(Debug, Clone)]
+pub struct Zone {
+    raw: Vec<u8>,
+}
+
+impl Default for Zone {
+    fn default() -> Self {
+        Zone { raw: vec![0; Self::SIZE] }
+    }
+}
+
+impl Zone {
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+
+    pub fn location_name_index(&self) -> u16 {
+        u16::from_le_bytes([self.raw[0x1C], self.raw[0x1D]])
+    }
+}
+
+impl Record for Zone {
+    const SIZE: usize = 0x54;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error> {
+        let mut zone = Self::default();
+
+        input.read_exact(&mut zone.raw)?;
+
+        Ok(zone)
+    }
+
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        Ok(output.write_all(&self.raw)?)
+    }
+}
@@ -0,0 +1,129 @@
+use super::super::error::Error;
+use byteorder::LittleEndian;
+use byteorder::ReadBytesExt;
+use byteorder::WriteBytesExt;
+use std::io::Read;
+use std::io::Write;
+use super::table::Record;
+
+// One GARC entry of a/0/1/9 per item.
+#[derive(Debug, Default, Clone)]
+pub struct Item {
+    pub price: u16,
+    pub held_effect: u8,
+    pub held_argument: u8,
+    pub natural_gift_effect: u8,
+    pub fling_effect: u8,
+    pub fling_power: u8,
+    pub natural_gift_power: u8,
+    pub packed: u16,
+    pub field_effect: u8,
+    pub battle_effect: u8,
+    pub unknown0: u8,
+    pub unknown1: u8,
+    pub battle_pocket: u8,
+    pub sort_index: u8,
+    pub cure_inflict: u8,
+    pub boosts: [u8; 4],
+    pub function_flags: [u8; 2],
+    pub ev_yields: [i8; 6],
+    pub heal_amount: u8,
+    pub pp_gain: u8,
+    pub friendship: [i8; 3],
+    pub unknown2: [u8; 2],
+}
+
+impl Item {
+    // prices are stored divided by ten.
+    pub fn buy_price(&self) -> u32 {
+        self.price as u32 * 10
+    }
+
+    pub fn sell_price(&self) -> u32 {
+        self.buy_price() / 2
+    }
+
+    pub fn natural_gift_type(&self) -> u8 {
+        (self.packed & 0x1F) as u8
+    }
+
+    pub fn pocket(&self) -> u8 {
+        ((self.packed >> 7) & 0xF) as u8
+    }
+
+    pub fn usable_in_field(&self) -> bool {
+        self.field_effect != 0
+    }
+
+    pub fn usable_in_battle(&self) -> bool {
+        self.battle_effect != 0
+    }
+}
+
+impl Record for Item {
+    const SIZE: usize = 0x24;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error> {
+        let mut record = Self::default();
+
+        record.price = input.read_u16::<LittleEndian>()?;
+        record.held_effect = input.read_u8()?;
+        record.held_argument = input.read_u8()?;
+        record.natural_gift_effect = input.read_u8()?;
+        record.fling_effect = input.read_u8()?;
+        record.fling_power = input.read_u8()?;
+        record.natural_gift_power = input.read_u8()?;
+        record.packed = input.read_u16::<LittleEndian>()?;
+        record.field_effect = input.read_u8()?;
+        record.battle_effect = input.read_u8()?;
+        record.unknown0 = input.read_u8()?;
+        record.unknown1 = input.read_u8()?;
+        record.battle_pocket = input.read_u8()?;
+        record.sort_index = input.read_u8()?;
+        record.cure_inflict = input.read_u8()?;
+        input.read_exact(&mut record.boosts)?;
+        input.read_exact(&mut record.function_flags)?;
+        for ev in record.ev_yields.iter_mut() {
+            *ev = input.read_i8()?;
+        }
+        record.heal_amount = input.read_u8()?;
+        record.pp_gain = input.read_u8()?;
+        for friendship in record.friendship.iter_mut() {
+            *friendship = input.read_i8()?;
+        }
+        input.read_exact(&mut record.unknown2)?;
+
+        Ok(record)
+    }
+
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        output.write_u16::<LittleEndian>(self.price)?;
+        output.write_u8(self.held_effect)?;
+        output.write_u8(self.held_argument)?;
+        output.write_u8(self.natural_gift_effect)?;
+        output.write_u8(self.fling_effect)?;
+        output.write_u8(self.fling_power)?;
+        output.write_u8(self.natural_gift_power)?;
+        output.write_u16::<LittleEndian>(self.packed)?;
+        output.write_u8(self.field_effect)?;
+        output.write_u8(self.battle_effect)?;
+        output.write_u8(self.unknown0)?;
+        output.write_u8(self.unknown1)?;
+        output.write_u8(self.battle_pocket)?;
+        output.write_u8(self.sort_index)?;
+        output.write_u8(self.cure_inflict)?;
+        output.write_all(&self.boosts)?;
+        output.write_all(&self.function_flags)?;
+        for ev in self.ev_yields.iter() {
+            output.write_i8(*ev)?;
+        }
+        output.write_u8(self.heal_amount)?;
+        output.write_u8(self.pp_gain)?;
+        for friendship in self.friendship.iter() {
+            output.write_i8(*friendship)?;
+        }
+        output.write_all(&self.unknown2)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use super::super::error::Error;
+use byteorder::LittleEndian;
+use byteorder::ReadBytesExt;
+use byteorder::WriteBytesExt;
+use std::io::Write;
+use super::super::read::Reader;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LevelUpMove {
+    pub move_id: u16,
+    pub level: u16,
+}
+
+// Level up moves for a species or form, the same in X/Y, OR/AS and Sun/Moon.
+// A list of (move, level) pairs, terminated by a 0xFFFF move.
+#[derive(Debug, Default, Clone)]
+pub struct Learnset {
+    pub moves: Vec<LevelUpMove>,
+}
+
+impl Learnset {
+    pub fn read(input: &mut Reader) -> Result<Self, Error> {
+        let mut learnset = Self::default();
+
+        for _ in 0..input.length() / 4 {
+            let move_id = input.read_u16::<LittleEndian>()?;
+            let level = input.read_u16::<LittleEndian>()?;
+
+            if move_id == 0xFFFF {
+                break;
+            }
+
+            learnset.moves.push(LevelUpMove { move_id, level });
+        }
+
+        Ok(learnset)
+    }
+
+    pub fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        for m in &self.moves {
+            output.write_u16::<LittleEndian>(m.move_id)?;
+            output.write_u16::<LittleEndian>(m.level)?;
+        }
+
+        Ok(output.write_u32::<LittleEndian>(0xFFFFFFFF)?)
+    }
+}
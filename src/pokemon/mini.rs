@@ -0,0 +1,53 @@
+use super::super::error::Error;
+use super::super::read::Reader;
+use super::table::Header;
+use super::table::Record;
+
+// Two letter packs (WD, EV, PC, ...). Same layout as BL tables, but each entry
+// usually holds a single record for one species, move, etc.
+#[derive(Debug)]
+pub struct Mini<'a> {
+    file: Reader<'a>,
+    header: Header,
+}
+
+impl<'a> Mini<'a> {
+    pub fn new(mut file: Reader<'a>, magic: [u8; 2]) -> Result<Self, Error> {
+        let header = Header::read(&mut file)?;
+        if header.magic() != magic {
+            return Err(Error::bad_magic(&magic, &header.magic(), 0));
+        }
+
+        Ok(Mini { file, header })
+    }
+
+    pub fn magic(&self) -> [u8; 2] {
+        self.header.magic()
+    }
+
+    pub fn len(&self) -> u16 {
+        self.header.table_count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn entry(&self, index: usize) -> Result<Reader<'a>, Error> {
+        self.header.entry(&self.file, index)
+    }
+
+    pub fn record<T: Record>(&self, index: usize) -> Result<T, Error> {
+        let mut file = self.entry(index)?;
+
+        if file.length() < T::SIZE as u64 {
+            return Err(Error::out_of_bounds("mini pack record", 0, T::SIZE as u64, file.length()));
+        }
+
+        T::read(&mut file)
+    }
+
+    pub fn records<T: Record>(&self) -> Result<Vec<T>, Error> {
+        (0..self.len() as usize).map(|i| self.record(i)).collect()
+    }
+}
@@ -0,0 +1,138 @@
+use super::super::error::Error;
+use byteorder::LittleEndian;
+use byteorder::ReadBytesExt;
+use byteorder::WriteBytesExt;
+use std::io::Read;
+use std::io::Write;
+use super::table::Record;
+
+// One entry of the WD mini pack in a/0/1/1.
+#[derive(Debug, Default, Clone)]
+pub struct Move {
+    pub move_type: u8,
+    pub quality: u8,
+    pub category: u8,
+    pub power: u8,
+    pub accuracy: u8,
+    pub pp: u8,
+    pub priority: i8,
+    pub hit_min: u8,
+    pub hit_max: u8,
+    pub inflict: u16,
+    pub inflict_percent: u8,
+    pub inflict_duration: u8,
+    pub turn_min: u8,
+    pub turn_max: u8,
+    pub crit_stage: u8,
+    pub flinch: u8,
+    pub effect_sequence: u16,
+    pub recoil: i8,
+    pub healing: u8,
+    pub target: u8,
+    pub stats: [u8; 3],
+    pub stat_stages: [i8; 3],
+    pub stat_percents: [u8; 3],
+    pub z_move: u16,
+    pub z_power: u8,
+    pub z_effect: u8,
+    pub refresh_type: u8,
+    pub refresh_percent: u8,
+    pub flags: u32,
+}
+
+impl Move {
+    // X/Y and OR/AS moves are 0x22 bytes: there are no Z-moves or refresh
+    // fields, and the flags come right after the stat changes.
+    pub const GEN6_SIZE: usize = 0x22;
+
+    pub fn read_gen6(input: &mut dyn Read) -> Result<Self, Error> {
+        let mut buffer = [0; 0x28];
+        input.read_exact(&mut buffer[..0x1E])?;
+        input.read_exact(&mut buffer[0x24..])?;
+
+        Self::read(&mut &buffer[..])
+    }
+}
+
+impl Record for Move {
+    const SIZE: usize = 0x28;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error> {
+        let mut record = Self::default();
+
+        record.move_type = input.read_u8()?;
+        record.quality = input.read_u8()?;
+        record.category = input.read_u8()?;
+        record.power = input.read_u8()?;
+        record.accuracy = input.read_u8()?;
+        record.pp = input.read_u8()?;
+        record.priority = input.read_i8()?;
+
+        let hits = input.read_u8()?;
+        record.hit_min = hits & 0xF;
+        record.hit_max = hits >> 4;
+
+        record.inflict = input.read_u16::<LittleEndian>()?;
+        record.inflict_percent = input.read_u8()?;
+        record.inflict_duration = input.read_u8()?;
+        record.turn_min = input.read_u8()?;
+        record.turn_max = input.read_u8()?;
+        record.crit_stage = input.read_u8()?;
+        record.flinch = input.read_u8()?;
+        record.effect_sequence = input.read_u16::<LittleEndian>()?;
+        record.recoil = input.read_i8()?;
+        record.healing = input.read_u8()?;
+        record.target = input.read_u8()?;
+
+        input.read_exact(&mut record.stats)?;
+        for stage in record.stat_stages.iter_mut() {
+            *stage = input.read_i8()?;
+        }
+        input.read_exact(&mut record.stat_percents)?;
+
+        record.z_move = input.read_u16::<LittleEndian>()?;
+        record.z_power = input.read_u8()?;
+        record.z_effect = input.read_u8()?;
+        record.refresh_type = input.read_u8()?;
+        record.refresh_percent = input.read_u8()?;
+
+        record.flags = input.read_u32::<LittleEndian>()?;
+
+        Ok(record)
+    }
+
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        output.write_u8(self.move_type)?;
+        output.write_u8(self.quality)?;
+        output.write_u8(self.category)?;
+        output.write_u8(self.power)?;
+        output.write_u8(self.accuracy)?;
+        output.write_u8(self.pp)?;
+        output.write_i8(self.priority)?;
+        output.write_u8((self.hit_max << 4) | (self.hit_min & 0xF))?;
+        output.write_u16::<LittleEndian>(self.inflict)?;
+        output.write_u8(self.inflict_percent)?;
+        output.write_u8(self.inflict_duration)?;
+        output.write_u8(self.turn_min)?;
+        output.write_u8(self.turn_max)?;
+        output.write_u8(self.crit_stage)?;
+        output.write_u8(self.flinch)?;
+        output.write_u16::<LittleEndian>(self.effect_sequence)?;
+        output.write_i8(self.recoil)?;
+        output.write_u8(self.healing)?;
+        output.write_u8(self.target)?;
+        output.write_all(&self.stats)?;
+        for stage in self.stat_stages.iter() {
+            output.write_i8(*stage)?;
+        }
+        output.write_all(&self.stat_percents)?;
+        output.write_u16::<LittleEndian>(self.z_move)?;
+        output.write_u8(self.z_power)?;
+        output.write_u8(self.z_effect)?;
+        output.write_u8(self.refresh_type)?;
+        output.write_u8(self.refresh_percent)?;
+        output.write_u32::<LittleEndian>(self.flags)?;
+
+        Ok(())
+    }
+}
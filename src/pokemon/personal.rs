@@ -0,0 +1,111 @@
+use super::super::error::Error;
+use byteorder::LittleEndian;
+use byteorder::ReadBytesExt;
+use byteorder::WriteBytesExt;
+use std::io::Read;
+use std::io::Write;
+
+// Personal information for a species or form. The first 0x28 bytes are the
+// same in X/Y (0x40 bytes), OR/AS (0x50) and Sun/Moon (0x54). The TM, tutor
+// and Z-move fields after them change between games and are kept raw.
+#[derive(Debug, Default, Clone)]
+pub struct Personal {
+    pub base_stats: [u8; 6],
+    pub types: [u8; 2],
+    pub catch_rate: u8,
+    pub evolution_stage: u8,
+    pub ev_yield: u16,
+    pub items: [u16; 3],
+    pub gender: u8,
+    pub hatch_cycles: u8,
+    pub base_friendship: u8,
+    pub growth_rate: u8,
+    pub egg_groups: [u8; 2],
+    pub abilities: [u8; 3],
+    pub escape_rate: u8,
+    pub form_stats_index: u16,
+    pub form_sprite: u16,
+    pub form_count: u8,
+    pub color: u8,
+    pub base_experience: u16,
+    pub height: u16,
+    pub weight: u16,
+    pub extra: Vec<u8>,
+}
+
+impl Personal {
+    pub fn read(input: &mut dyn Read, size: usize) -> Result<Self, Error> {
+        let mut personal = Self::default();
+
+        input.read_exact(&mut personal.base_stats)?;
+        input.read_exact(&mut personal.types)?;
+        personal.catch_rate = input.read_u8()?;
+        personal.evolution_stage = input.read_u8()?;
+        personal.ev_yield = input.read_u16::<LittleEndian>()?;
+        for item in personal.items.iter_mut() {
+            *item = input.read_u16::<LittleEndian>()?;
+        }
+        personal.gender = input.read_u8()?;
+        personal.hatch_cycles = input.read_u8()?;
+        personal.base_friendship = input.read_u8()?;
+        personal.growth_rate = input.read_u8()?;
+        input.read_exact(&mut personal.egg_groups)?;
+        input.read_exact(&mut personal.abilities)?;
+        personal.escape_rate = input.read_u8()?;
+        personal.form_stats_index = input.read_u16::<LittleEndian>()?;
+        personal.form_sprite = input.read_u16::<LittleEndian>()?;
+        personal.form_count = input.read_u8()?;
+        personal.color = input.read_u8()?;
+        personal.base_experience = input.read_u16::<LittleEndian>()?;
+        personal.height = input.read_u16::<LittleEndian>()?;
+        personal.weight = input.read_u16::<LittleEndian>()?;
+
+        personal.extra.resize(size.saturating_sub(0x28), 0);
+        input.read_exact(&mut personal.extra)?;
+
+        Ok(personal)
+    }
+
+    pub fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        output.write_all(&self.base_stats)?;
+        output.write_all(&self.types)?;
+        output.write_u8(self.catch_rate)?;
+        output.write_u8(self.evolution_stage)?;
+        output.write_u16::<LittleEndian>(self.ev_yield)?;
+        for item in self.items.iter() {
+            output.write_u16::<LittleEndian>(*item)?;
+        }
+        output.write_u8(self.gender)?;
+        output.write_u8(self.hatch_cycles)?;
+        output.write_u8(self.base_friendship)?;
+        output.write_u8(self.growth_rate)?;
+        output.write_all(&self.egg_groups)?;
+        output.write_all(&self.abilities)?;
+        output.write_u8(self.escape_rate)?;
+        output.write_u16::<LittleEndian>(self.form_stats_index)?;
+        output.write_u16::<LittleEndian>(self.form_sprite)?;
+        output.write_u8(self.form_count)?;
+        output.write_u8(self.color)?;
+        output.write_u16::<LittleEndian>(self.base_experience)?;
+        output.write_u16::<LittleEndian>(self.height)?;
+        output.write_u16::<LittleEndian>(self.weight)?;
+        output.write_all(&self.extra)?;
+
+        Ok(())
+    }
+
+    // 2 bits per stat, in HP, Attack, Defense, Speed, Sp. Atk, Sp. Def order.
+    pub fn ev_yields(&self) -> [u8; 6] {
+        let mut yields = [0; 6];
+        for (i, value) in yields.iter_mut().enumerate() {
+            *value = ((self.ev_yield >> (2 * i)) & 0x3) as u8;
+        }
+
+        yields
+    }
+
+    // TM and HM compatibility, one bit per machine.
+    pub fn tm_compatibility(&self) -> &[u8] {
+        &self.extra[..self.extra.len().min(0x10)]
+    }
+}
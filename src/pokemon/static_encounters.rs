@@ -0,0 +1,161 @@
+use super::super::error::Error;
+use std::io::Read;
+use std::io::Write;
+use super::table::Record;
+
+// Gifts, static encounters and in-game trades share their first four bytes.
+// The remaining fields aren't mapped yet and are kept as is, so records can
+// be edited and written back without losing anything. They stay private so
+// a record always has exactly SIZE of them.
+fn read_raw(input: &mut dyn Read, size: usize) -> Result<Vec<u8>, Error> {
+    let mut raw = vec![0; size];
+    input.read_exact(&mut raw)?;
+
+    Ok(raw)
+}
+
+fn write_common(raw: &mut [u8], species: u16, form: u8, level: u8) {
+    raw[0..2].copy_from_slice(&species.to_le_bytes());
+    raw[2] = form;
+    raw[3] = level;
+}
+
+#[derive(Debug, Clone)]
+pub struct Gift {
+    pub species: u16,
+    pub form: u8,
+    pub level: u8,
+    raw: Vec<u8>,
+}
+
+impl Default for Gift {
+    fn default() -> Self {
+        Gift { species: 0, form: 0, level: 0, raw: vec![0; Self::SIZE] }
+    }
+}
+
+impl Gift {
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+}
+
+impl Record for Gift {
+    const SIZE: usize = 0x14;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error> {
+        let raw = read_raw(input, Self::SIZE)?;
+
+        Ok(Gift {
+            species: u16::from_le_bytes([raw[0], raw[1]]),
+            form: raw[2],
+            level: raw[3],
+            raw,
+        })
+    }
+
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        let mut raw = self.raw.clone();
+        write_common(&mut raw, self.species, self.form, self.level);
+
+        Ok(output.write_all(&raw)?)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StaticEncounter {
+    pub species: u16,
+    pub form: u8,
+    pub level: u8,
+    pub held_item: i16,
+    pub relearn_moves: [u16; 4],
+    raw: Vec<u8>,
+}
+
+impl Default for StaticEncounter {
+    fn default() -> Self {
+        StaticEncounter { species: 0, form: 0, level: 0, held_item: 0, relearn_moves: [0; 4], raw: vec![0; Self::SIZE] }
+    }
+}
+
+impl StaticEncounter {
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+}
+
+impl Record for StaticEncounter {
+    const SIZE: usize = 0x38;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error> {
+        let raw = read_raw(input, Self::SIZE)?;
+
+        let mut relearn_moves = [0; 4];
+        for (i, m) in relearn_moves.iter_mut().enumerate() {
+            *m = u16::from_le_bytes([raw[0xC + 2 * i], raw[0xD + 2 * i]]);
+        }
+
+        Ok(StaticEncounter {
+            species: u16::from_le_bytes([raw[0], raw[1]]),
+            form: raw[2],
+            level: raw[3],
+            held_item: i16::from_le_bytes([raw[4], raw[5]]),
+            relearn_moves,
+            raw,
+        })
+    }
+
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        let mut raw = self.raw.clone();
+        write_common(&mut raw, self.species, self.form, self.level);
+
+        raw[4..6].copy_from_slice(&self.held_item.to_le_bytes());
+        for (i, m) in self.relearn_moves.iter().enumerate() {
+            raw[0xC + 2 * i..0xE + 2 * i].copy_from_slice(&m.to_le_bytes());
+        }
+
+        Ok(output.write_all(&raw)?)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Trade {
+    pub species: u16,
+    pub form: u8,
+    pub level: u8,
+    raw: Vec<u8>,
+}
+
+impl Default for Trade {
+    fn default() -> Self {
+        Trade { species: 0, form: 0, level: 0, raw: vec![0; Self::SIZE] }
+    }
+}
+
+impl Trade {
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+}
+
+impl Record for Trade {
+    const SIZE: usize = 0x34;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error> {
+        let raw = read_raw(input, Self::SIZE)?;
+
+        Ok(Trade {
+            species: u16::from_le_bytes([raw[0], raw[1]]),
+            form: raw[2],
+            level: raw[3],
+            raw,
+        })
+    }
+
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        let mut raw = self.raw.clone();
+        write_common(&mut raw, self.species, self.form, self.level);
+
+        Ok(output.write_all(&raw)?)
+    }
+}
@@ -1,5 +1,10 @@
+use super::super::error::Error;
 use byteorder::LittleEndian;
 use byteorder::ReadBytesExt;
+use byteorder::WriteBytesExt;
+use std::convert::TryFrom;
+use std::io::Read;
+use std::io::Write;
 use super::super::read::Reader;
 
 #[derive(Debug)]
@@ -9,12 +14,16 @@ pub struct Table<'a> {
 }
 
 impl<'a> Table<'a> {
-    pub fn new(mut file: Reader<'a>) -> Result<Self, std::io::Error> {
+    pub fn new(mut file: Reader<'a>) -> Result<Self, Error> {
         let header = Header::read(&mut file)?;
 
         Ok(Table { file, header })
     }
 
+    pub fn magic(&self) -> [u8; 2] {
+        self.header.magic
+    }
+
     pub fn table_count(&self) -> u16 {
         self.header.table_count
     }
@@ -29,6 +38,26 @@ impl<'a> Table<'a> {
             index: 0,
         }
     }
+
+    pub fn entry(&self, index: usize) -> Result<Reader<'a>, Error> {
+        self.header.entry(&self.file, index)
+    }
+
+    pub fn records<T: Record>(&self, index: usize) -> Result<Vec<T>, Error> {
+        read_records(&mut self.entry(index)?)
+    }
+
+    pub fn u8s(&self, index: usize) -> Result<Vec<u8>, Error> {
+        self.records(index)
+    }
+
+    pub fn u16s(&self, index: usize) -> Result<Vec<u16>, Error> {
+        self.records(index)
+    }
+
+    pub fn u32s(&self, index: usize) -> Result<Vec<u32>, Error> {
+        self.records(index)
+    }
 }
 
 pub struct TableIterator<'a, 'b> {
@@ -37,48 +66,192 @@ pub struct TableIterator<'a, 'b> {
 }
 
 impl<'a, 'b> Iterator for TableIterator<'a, 'b> {
-    type Item = Reader<'a>;
-    fn next(&mut self) -> Option<Reader<'a>> {
+    type Item = Result<Reader<'a>, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
         if self.index == self.table.header.table_count {
             None
         } else {
             let idx = self.index as usize;
             self.index += 1;
 
-            Some(self.table.file.limit(
-                self.table.header.table_offsets[idx] as u64,
-                (self.table.header.table_offsets[idx + 1] - self.table.header.table_offsets[idx]) as u64,
-            ).unwrap())
+            Some(self.table.entry(idx))
+        }
+    }
+}
+
+// Fixed size element of a table. Tables are decoded as a packed array of
+// these, so `read` and `write` must consume and produce exactly SIZE bytes.
+pub trait Record: Sized {
+    const SIZE: usize;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error>;
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error>;
+}
+
+impl Record for u8 {
+    const SIZE: usize = 1;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error> {
+        Ok(input.read_u8()?)
+    }
+
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        Ok(output.write_u8(*self)?)
+    }
+}
+
+impl Record for u16 {
+    const SIZE: usize = 2;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error> {
+        Ok(input.read_u16::<LittleEndian>()?)
+    }
+
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        Ok(output.write_u16::<LittleEndian>(*self)?)
+    }
+}
+
+impl Record for u32 {
+    const SIZE: usize = 4;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error> {
+        Ok(input.read_u32::<LittleEndian>()?)
+    }
+
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        Ok(output.write_u32::<LittleEndian>(*self)?)
+    }
+}
+
+// Decodes a whole file as a packed array of records.
+pub fn read_records<T: Record>(file: &mut Reader) -> Result<Vec<T>, Error> {
+    if !file.length().is_multiple_of(T::SIZE as u64) {
+        return Err(Error::malformed(format!("length {:#x} is not a multiple of the record size {:#x}", file.length(), T::SIZE)));
+    }
+
+    (0..file.length() / T::SIZE as u64).map(|_| T::read(file)).collect()
+}
+
+pub fn write_records<T: Record>(records: &[T], output: &mut dyn Write) -> Result<(), Error> {
+    for record in records {
+        record.write(output)?;
+    }
+
+    Ok(())
+}
+
+// Builds a table file. Tables are written back to back, so each one reads back
+// exactly as it was pushed. align(4) pads them like the games do, but the
+// padding then reads back as part of the table, e.g. u8s gives [1, 2, 3, 0].
+#[derive(Debug)]
+pub struct TableBuilder {
+    magic: [u8; 2],
+    alignment: usize,
+    tables: Vec<Vec<u8>>,
+}
+
+impl Default for TableBuilder {
+    fn default() -> Self {
+        Self::with_magic(*b"BL")
+    }
+}
+
+impl TableBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_magic(magic: [u8; 2]) -> Self {
+        TableBuilder { magic, alignment: 1, tables: vec![] }
+    }
+
+    pub fn align(&mut self, alignment: usize) -> &mut Self {
+        self.alignment = alignment.max(1);
+        self
+    }
+
+    pub fn push(&mut self, table: Vec<u8>) -> &mut Self {
+        self.tables.push(table);
+        self
+    }
+
+    pub fn push_records<T: Record>(&mut self, records: &[T]) -> Result<&mut Self, Error> {
+        let mut table = vec![];
+        write_records(records, &mut table)?;
+
+        Ok(self.push(table))
+    }
+
+    pub fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        let count = self.tables.len();
+        let table_count = u16::try_from(count).map_err(|_| Error::out_of_bounds("table count", count as u64, 0, u16::MAX as u64))?;
+
+        // Offsets are summed in u64 so one past what the header can hold is
+        // caught instead of wrapping.
+        let mut offset = 4 + (count as u64 + 1) * 4;
+        let mut table_offsets = vec![];
+        for length in std::iter::once(0).chain(self.tables.iter().map(|table| self.padded_length(table.len()))) {
+            offset += length as u64;
+            table_offsets.push(u32::try_from(offset).map_err(|_| Error::out_of_bounds("table offset", offset, 0, u32::MAX as u64))?);
         }
+
+        let header = Header { magic: self.magic, table_count, table_offsets };
+
+        header.write(output)?;
+
+        for table in &self.tables {
+            output.write_all(table)?;
+            output.write_all(&vec![0; self.padded_length(table.len()) - table.len()])?;
+        }
+
+        Ok(())
+    }
+
+    pub fn build(&self) -> Result<Vec<u8>, Error> {
+        let mut output = vec![];
+        self.write(&mut output)?;
+
+        Ok(output)
+    }
+
+    fn padded_length(&self, length: usize) -> usize {
+        length.div_ceil(self.alignment) * self.alignment
     }
 }
 
+// Shared by BL tables and every other two letter pack (WD, EV, PC, BS, ...).
 #[derive(Debug, Default)]
 pub struct Header {
-    magic: u16,
+    magic: [u8; 2],
     table_count: u16,
     table_offsets: Vec<u32>,
 }
 
 impl Header {
-    pub fn read(input: &mut Reader) -> Result<Self, std::io::Error> {
+    pub fn read(input: &mut Reader) -> Result<Self, Error> {
         let mut header = Header::default();
 
-        header.magic = input.read_u16::<LittleEndian>()?;
-        if !(header.magic == 0x4c42) {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "expected BL magic number"));
+        input.read_exact(&mut header.magic)?;
+        if !header.magic.iter().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+            return Err(Error::malformed(format!("expected a two letter magic number, found {:02x?}", header.magic)));
         }
 
         header.table_count = input.read_u16::<LittleEndian>()?;
-        assert!(4 + (header.table_count as u64 + 1) * 4 <= input.length());
+        if 4 + (header.table_count as u64 + 1) * 4 > input.length() {
+            return Err(Error::out_of_bounds("table offsets", 4, (header.table_count as u64 + 1) * 4, input.length()));
+        }
 
         for i in 0..=header.table_count as usize {
             let offset = input.read_u32::<LittleEndian>()?;
 
-            assert!((offset as u64) <= input.length());
+            if offset as u64 > input.length() {
+                return Err(Error::out_of_bounds("table", offset as u64, 0, input.length()));
+            }
+
             if i > 0 {
-                if !(header.table_offsets[i - 1] < offset) {
-                    return Err(std::io::Error::new(std::io::ErrorKind::Other, "dunno, don't remember anything"));
+                if !(header.table_offsets[i - 1] <= offset) {
+                    return Err(Error::malformed("table offsets out of order"));
                 }
             }
 
@@ -87,4 +260,34 @@ impl Header {
 
         Ok(header)
     }
+
+    pub fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        output.write_all(&self.magic)?;
+        output.write_u16::<LittleEndian>(self.table_count)?;
+
+        for offset in &self.table_offsets {
+            output.write_u32::<LittleEndian>(*offset)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn magic(&self) -> [u8; 2] {
+        self.magic
+    }
+
+    pub fn table_count(&self) -> u16 {
+        self.table_count
+    }
+
+    pub fn entry<'a>(&self, file: &Reader<'a>, index: usize) -> Result<Reader<'a>, Error> {
+        if index >= self.table_count as usize {
+            return Err(Error::out_of_bounds("table index", index as u64, 1, self.table_count as u64));
+        }
+
+        file.limit(
+            self.table_offsets[index] as u64,
+            (self.table_offsets[index + 1] - self.table_offsets[index]) as u64,
+        )
+    }
 }
@@ -1,9 +1,13 @@
+use super::super::error::Error;
 use byteorder::ReadBytesExt;
+use byteorder::WriteBytesExt;
 use byteorder::ByteOrder;
 use byteorder::LittleEndian;
+use std::convert::TryFrom;
 use std::io::SeekFrom;
 use std::io::Read;
 use std::io::Seek;
+use std::io::Write;
 use super::super::read::Reader;
 
 #[derive(Debug)]
@@ -13,74 +17,293 @@ pub struct Texts<'a> {
 }
 
 impl<'a> Texts<'a> {
-    pub fn new(mut file: Reader<'a>) -> Result<Self, std::io::Error> {
+    pub fn new(mut file: Reader<'a>) -> Result<Self, Error> {
         let header = Header::read(&mut file)?;
 
         Ok(Self { file, header })
     }
 
+    pub fn section_count(&self) -> u16 {
+        self.header.section_count
+    }
+
+    pub fn line_count(&self) -> u16 {
+        self.header.line_count
+    }
+
     pub fn entries<'b>(&'b self) -> TextIterator<'a, 'b> {
         TextIterator {
             file: self.file.clone(),
             header: &self.header,
+            section: self.header.sections.first(),
             index: 0,
         }
     }
+
+    pub fn tokens<'b>(&'b self) -> TokenIterator<'a, 'b> {
+        self.entries().tokens()
+    }
+
+    pub fn lines<'b>(&'b self) -> LineIterator<'a, 'b> {
+        self.entries().lines()
+    }
+
+    pub fn section<'b>(&'b self, index: usize) -> Result<TextIterator<'a, 'b>, Error> {
+        let section = self.header.sections.get(index).ok_or_else(|| Error::out_of_bounds("text section", index as u64, 1, self.header.sections.len() as u64))?;
+
+        Ok(TextIterator {
+            file: self.file.clone(),
+            header: &self.header,
+            section: Some(section),
+            index: 0,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextLine {
+    pub text: String,
+    pub flags: u16,
 }
 
 pub struct TextIterator<'a, 'b> {
     file: Reader<'a>,
     header: &'b Header,
+    section: Option<&'b Section>,
     index: u16,
 }
 
 impl<'a, 'b> Iterator for TextIterator<'a, 'b> {
-    type Item = Result<String, std::io::Error>;
-    fn next(&mut self) -> Option<Result<String, std::io::Error>> {
+    type Item = Result<String, Error>;
+    fn next(&mut self) -> Option<Result<String, Error>> {
         self.try_next().transpose()
     }
 }
 
 impl<'a, 'b> TextIterator<'a, 'b> {
-    fn try_next(&mut self) -> Result<Option<String>, std::io::Error> {
-        if self.index < self.header.line_count {
-            self.file.seek(SeekFrom::Start(20 + self.index as u64 * 8))?;
-            let entry = LineEntry::read(&mut self.file)?;
+    pub fn tokens(self) -> TokenIterator<'a, 'b> {
+        TokenIterator { lines: self }
+    }
+
+    pub fn lines(self) -> LineIterator<'a, 'b> {
+        LineIterator { lines: self }
+    }
 
-            self.file.seek(SeekFrom::Start(16 + entry.offset as u64))?;
-            let poop = entry.read_line(&mut self.file, self.index)?;
+    pub fn try_next(&mut self) -> Result<Option<String>, Error> {
+        Ok(self.try_next_tokens()?.map(|tokens| render(&tokens)))
+    }
 
-            self.index += 1;
+    pub fn try_next_tokens(&mut self) -> Result<Option<Vec<TextToken>>, Error> {
+        Ok(self.try_next_units()?.map(|(line, _)| tokenize(&line)))
+    }
 
-            Ok(Some(poop))
-        } else {
-            Ok(None)
+    pub fn try_next_line(&mut self) -> Result<Option<TextLine>, Error> {
+        Ok(self.try_next_units()?.map(|(line, flags)| TextLine { text: render(&tokenize(&line)), flags }))
+    }
+
+    fn try_next_units(&mut self) -> Result<Option<(Vec<u16>, u16)>, Error> {
+        match self.section {
+            Some(section) if self.index < self.header.line_count => {
+                self.file.seek(SeekFrom::Start(section.offset + 4 + self.index as u64 * 8))?;
+                let entry = LineEntry::read(&mut self.file)?;
+
+                if entry.offset as u64 + 2 * entry.length as u64 > section.length {
+                    return Err(Error::out_of_bounds("text line", entry.offset as u64, 2 * entry.length as u64, section.length));
+                }
+
+                self.file.seek(SeekFrom::Start(section.offset + entry.offset as u64))?;
+                let line = entry.read_line(&mut self.file, self.index)?;
+
+                self.index += 1;
+
+                Ok(Some((line, entry.flags)))
+            },
+            _ => Ok(None),
         }
     }
 }
 
+pub struct TokenIterator<'a, 'b> {
+    lines: TextIterator<'a, 'b>,
+}
+
+impl<'a, 'b> Iterator for TokenIterator<'a, 'b> {
+    type Item = Result<Vec<TextToken>, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.try_next_tokens().transpose()
+    }
+}
+
+pub struct LineIterator<'a, 'b> {
+    lines: TextIterator<'a, 'b>,
+}
+
+impl<'a, 'b> Iterator for LineIterator<'a, 'b> {
+    type Item = Result<TextLine, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.try_next_line().transpose()
+    }
+}
+
+// Builds a text file from lines in the markup produced by `render`. Every
+// section must have the same number of lines.
+#[derive(Debug)]
+pub struct TextsBuilder {
+    sections: Vec<Vec<(Vec<u16>, u16)>>,
+}
+
+impl Default for TextsBuilder {
+    fn default() -> Self {
+        TextsBuilder { sections: vec![vec![]] }
+    }
+}
+
+impl TextsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, line: &str) -> Result<&mut Self, Error> {
+        let tokens = parse(line)?;
+
+        self.push_tokens(&tokens, 0)
+    }
+
+    pub fn push_line(&mut self, line: &TextLine) -> Result<&mut Self, Error> {
+        let tokens = parse(&line.text)?;
+
+        self.push_tokens(&tokens, line.flags)
+    }
+
+    pub fn push_tokens(&mut self, tokens: &[TextToken], flags: u16) -> Result<&mut Self, Error> {
+        let last = self.sections.len() - 1;
+        self.sections[last].push((untokenize(tokens)?, flags));
+        Ok(self)
+    }
+
+    // Following lines go into a new section.
+    pub fn push_section(&mut self) -> &mut Self {
+        self.sections.push(vec![]);
+        self
+    }
+
+    pub fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        let line_count = self.sections[0].len();
+        if self.sections.iter().any(|section| section.len() != line_count) {
+            return Err(Error::malformed("text sections must have the same number of lines"));
+        }
+        let line_count = u16_count("text lines", line_count)?;
+        let section_count = u16_count("text sections", self.sections.len())?;
+
+        let sections = self.sections.iter().map(|section| {
+            section.iter().zip(0..).map(|((line, flags), i)| (encode(line, i), *flags)).collect::<Vec<_>>()
+        }).collect::<Vec<_>>();
+
+        // Lengths and offsets are counted in u64 so that anything too big
+        // for the header's u32s is caught instead of wrapping.
+        let table_length = 4 + 8 * line_count as u64;
+        let section_lengths = sections.iter().map(|lines| {
+            u32_offset("text section", table_length + lines.iter().map(|(line, _)| padded_length(line.len()) as u64).sum::<u64>())
+        }).collect::<Result<Vec<_>, _>>()?;
+
+        let mut section_offsets = vec![];
+        let mut section_offset = 12 + 4 * section_count as u64;
+        for length in &section_lengths {
+            section_offsets.push(u32_offset("text section", section_offset)?);
+            section_offset += *length as u64;
+        }
+
+        output.write_u16::<LittleEndian>(section_count)?;
+        output.write_u16::<LittleEndian>(line_count)?;
+        output.write_u32::<LittleEndian>(section_lengths.iter().cloned().max().unwrap_or(0))?;
+        output.write_u32::<LittleEndian>(0)?;
+
+        for offset in section_offsets {
+            output.write_u32::<LittleEndian>(offset)?;
+        }
+
+        for (lines, length) in sections.iter().zip(section_lengths) {
+            output.write_u32::<LittleEndian>(length)?;
+
+            let mut offset = table_length as u32;
+            for (line, flags) in lines {
+                output.write_u32::<LittleEndian>(offset)?;
+                output.write_u16::<LittleEndian>(u16_count("text line length", line.len() / 2)?)?;
+                output.write_u16::<LittleEndian>(*flags)?;
+
+                // Can't overflow: the whole section fit in a u32 above.
+                offset += padded_length(line.len()) as u32;
+            }
+
+            for (line, _) in lines {
+                output.write_all(line)?;
+                output.write_all(&vec![0; padded_length(line.len()) - line.len()])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn build(&self) -> Result<Vec<u8>, Error> {
+        let mut output = vec![];
+        self.write(&mut output)?;
+
+        Ok(output)
+    }
+}
+
+fn padded_length(length: usize) -> usize {
+    length.div_ceil(4) * 4
+}
 
-// Unsure where the section header begins.
 #[derive(Default, Debug)]
 pub struct Header {
     section_count: u16,
     pub line_count: u16,
     total_length: u32,
     initial_key: u32,
-    section_data_offset: u32,
-    section_length: u32,
+    sections: Vec<Section>,
+}
+
+#[derive(Default, Debug)]
+pub struct Section {
+    offset: u64,
+    length: u64,
 }
 
 impl Header {
-    pub fn read(input: &mut Reader) -> Result<Self, std::io::Error> {
+    pub fn read(input: &mut Reader) -> Result<Self, Error> {
         let mut header = Self::default();
 
         header.section_count = input.read_u16::<LittleEndian>()?;
         header.line_count = input.read_u16::<LittleEndian>()?;
         header.total_length = input.read_u32::<LittleEndian>()?;
         header.initial_key = input.read_u32::<LittleEndian>()?;
-        header.section_data_offset = input.read_u32::<LittleEndian>()?;
-        header.section_length = input.read_u32::<LittleEndian>()?;
+
+        // line keys are derived from the line index alone, so this is always zero.
+        if header.initial_key != 0 {
+            return Err(Error::UnsupportedVersion { what: "text initial key", version: header.initial_key as u64 });
+        }
+
+        let mut offsets = vec![];
+        for _ in 0..header.section_count {
+            offsets.push(input.read_u32::<LittleEndian>()? as u64);
+        }
+
+        for offset in offsets {
+            if offset + 4 + header.line_count as u64 * 8 > input.length() {
+                return Err(Error::out_of_bounds("text section", offset, 4 + header.line_count as u64 * 8, input.length()));
+            }
+
+            input.seek(SeekFrom::Start(offset))?;
+            let length = input.read_u32::<LittleEndian>()? as u64;
+
+            if offset + length > input.length() {
+                return Err(Error::out_of_bounds("text section", offset, length, input.length()));
+            }
+
+            header.sections.push(Section { offset, length });
+        }
 
         Ok(header)
     }
@@ -90,26 +313,26 @@ impl Header {
 struct LineEntry {
     offset: u32,
     length: u16,
-    unknown0: u16,
+    flags: u16,
 }
 
 impl LineEntry {
-    pub fn read(input: &mut Reader) -> Result<Self, std::io::Error> {
+    pub fn read(input: &mut Reader) -> Result<Self, Error> {
         let mut entry = Self::default();
 
         entry.offset = input.read_u32::<LittleEndian>()?;
         entry.length = input.read_u16::<LittleEndian>()?;
-        entry.unknown0 = input.read_u16::<LittleEndian>()?;
+        entry.flags = input.read_u16::<LittleEndian>()?;
 
         Ok(entry)
     }
 
-    pub fn read_line(&self, input: &mut Reader, n: u16) -> Result<String, std::io::Error> {
+    pub fn read_line(&self, input: &mut Reader, n: u16) -> Result<Vec<u16>, Error> {
         let mut buf = vec![];
         buf.resize(2 * self.length as usize, 0);
         input.read_exact(&mut buf)?;
 
-        decode(&buf, n)
+        Ok(decode(&buf, n))
     }
 }
 
@@ -117,17 +340,252 @@ fn key(index: u16) -> u16 {
     ((index as u32 * 0x2983 + 0x7C89) & 0xFFFF) as u16
 }
 
-fn decode(bytes: &[u8], index: u16) -> Result<String, std::io::Error> {
-    let mut text = String::new();
+fn decode(bytes: &[u8], index: u16) -> Vec<u16> {
+    let mut units = vec![];
 
     let mut key = key(index);
     for i in (0..bytes.len()).step_by(2) {
-        let character = LittleEndian::read_u16(&bytes[i..i+2]) ^ key;
+        units.push(LittleEndian::read_u16(&bytes[i..i+2]) ^ key);
+
+        key = key.rotate_left(3);
+    }
+
+    units
+}
 
-        text.push(char::from_u32(character as u32).unwrap());
+fn encode(units: &[u16], index: u16) -> Vec<u8> {
+    let mut bytes = vec![];
+
+    let mut key = key(index);
+    for unit in units {
+        bytes.extend_from_slice(&(unit ^ key).to_le_bytes());
 
         key = key.rotate_left(3);
     }
 
-    Ok(text.trim_end_matches('\x00').to_string())
+    bytes
+}
+
+const TERMINATOR: u16 = 0x0000;
+const VARIABLE: u16 = 0x0010;
+const VARIABLE_RETURN: u16 = 0xBE00;
+const VARIABLE_CLEAR: u16 = 0xBE01;
+const VARIABLE_WAIT: u16 = 0xBE02;
+const VARIABLE_NULL: u16 = 0xBDFF;
+
+// A line split into plain text and the escape sequences that start with 0x0010.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextToken {
+    Text(String),
+    // scroll to the next line, rendered as \r
+    Return,
+    // clear the text box, rendered as \c
+    Clear,
+    Wait(u16),
+    Null(u16),
+    // player name, numbers, colours, gender and plural branches, ruby text, ...
+    Variable { code: u16, args: Vec<u16> },
+    // code units that aren't valid characters, like unpaired surrogates.
+    Raw(u16),
+}
+
+pub fn tokenize(units: &[u16]) -> Vec<TextToken> {
+    let mut tokens = vec![];
+    let mut text = String::new();
+
+    let mut i = 0;
+    while i < units.len() && units[i] != TERMINATOR {
+        if units[i] == VARIABLE && i + 1 < units.len() && units[i + 1] > 0 && i + 2 + units[i + 1] as usize <= units.len() {
+            let count = units[i + 1] as usize;
+            let code = units[i + 2];
+            let args = units[i + 3..i + 2 + count].to_vec();
+
+            if !text.is_empty() {
+                tokens.push(TextToken::Text(std::mem::take(&mut text)));
+            }
+
+            tokens.push(match (code, args.as_slice()) {
+                (VARIABLE_RETURN, []) => TextToken::Return,
+                (VARIABLE_CLEAR, []) => TextToken::Clear,
+                (VARIABLE_WAIT, [time]) => TextToken::Wait(*time),
+                (VARIABLE_NULL, [line]) => TextToken::Null(*line),
+                _ => TextToken::Variable { code, args },
+            });
+
+            i += 2 + count;
+        } else {
+            match char::decode_utf16(units[i..].iter().cloned()).next() {
+                Some(Ok(c)) => {
+                    text.push(c);
+                    i += c.len_utf16();
+                },
+                _ => {
+                    if !text.is_empty() {
+                        tokens.push(TextToken::Text(std::mem::take(&mut text)));
+                    }
+                    tokens.push(TextToken::Raw(units[i]));
+                    i += 1;
+                },
+            }
+        }
+    }
+
+    if !text.is_empty() {
+        tokens.push(TextToken::Text(text));
+    }
+
+    tokens
+}
+
+pub fn render(tokens: &[TextToken]) -> String {
+    let mut output = String::new();
+
+    for token in tokens {
+        match token {
+            TextToken::Text(text) => {
+                for c in text.chars() {
+                    match c {
+                        '\n' => output.push_str("\\n"),
+                        '\\' => output.push_str("\\\\"),
+                        '[' => output.push_str("\\["),
+                        c => output.push(c),
+                    }
+                }
+            },
+            TextToken::Return => output.push_str("\\r"),
+            TextToken::Clear => output.push_str("\\c"),
+            TextToken::Wait(time) => output.push_str(&format!("[WAIT {}]", time)),
+            TextToken::Null(line) => output.push_str(&format!("[~ {}]", line)),
+            TextToken::Variable { code, args } => {
+                output.push_str(&format!("[VAR {:04X}", code));
+                if !args.is_empty() {
+                    let args = args.iter().map(|arg| format!("{:04X}", arg)).collect::<Vec<_>>();
+                    output.push_str(&format!("({})", args.join(",")));
+                }
+                output.push(']');
+            },
+            TextToken::Raw(unit) => output.push_str(&format!("[CHAR {:04X}]", unit)),
+        }
+    }
+
+    output
+}
+
+fn u16_count(what: &'static str, count: usize) -> Result<u16, Error> {
+    u16::try_from(count).map_err(|_| Error::out_of_bounds(what, count as u64, 0, u16::MAX as u64))
+}
+
+fn u32_offset(what: &'static str, offset: u64) -> Result<u32, Error> {
+    u32::try_from(offset).map_err(|_| Error::out_of_bounds(what, offset, 0, u32::MAX as u64))
+}
+
+// A terminator in the middle would cut the line short when it's read back,
+// and a stray variable marker would swallow the units after it.
+fn untokenize(tokens: &[TextToken]) -> Result<Vec<u16>, Error> {
+    let mut units = vec![];
+
+    for token in tokens {
+        match token {
+            TextToken::Text(text) if text.contains('\0') => { return Err(invalid_markup(format!("text contains a terminator: {:?}", text))); },
+            TextToken::Text(text) if text.contains('\u{10}') => { return Err(invalid_markup(format!("text contains a variable marker: {:?}", text))); },
+            TextToken::Raw(TERMINATOR) => { return Err(invalid_markup("text contains a terminator: [CHAR 0000]".to_string())); },
+            TextToken::Raw(VARIABLE) => { return Err(invalid_markup("text contains a variable marker: [CHAR 0010]".to_string())); },
+            TextToken::Text(text) => units.extend(text.encode_utf16()),
+            TextToken::Return => units.extend(&[VARIABLE, 1, VARIABLE_RETURN]),
+            TextToken::Clear => units.extend(&[VARIABLE, 1, VARIABLE_CLEAR]),
+            TextToken::Wait(time) => units.extend(&[VARIABLE, 2, VARIABLE_WAIT, *time]),
+            TextToken::Null(line) => units.extend(&[VARIABLE, 2, VARIABLE_NULL, *line]),
+            TextToken::Variable { code, args } => {
+                units.extend(&[VARIABLE, u16_count("text variable arguments", 1 + args.len())?, *code]);
+                units.extend(args);
+            },
+            TextToken::Raw(unit) => units.push(*unit),
+        }
+    }
+
+    units.push(TERMINATOR);
+
+    Ok(units)
+}
+
+fn invalid_markup(message: String) -> Error {
+    Error::malformed(message)
+}
+
+fn parse_hex(value: &str) -> Result<u16, Error> {
+    u16::from_str_radix(value.trim(), 16).map_err(|_| invalid_markup(format!("expected a hex number, found {:?}", value)))
+}
+
+fn parse_decimal(value: &str) -> Result<u16, Error> {
+    value.trim().parse().map_err(|_| invalid_markup(format!("expected a number, found {:?}", value)))
+}
+
+fn parse_command(command: &str) -> Result<TextToken, Error> {
+    let (name, value) = command.split_at(command.find(' ').unwrap_or(command.len()));
+
+    match name {
+        "WAIT" => Ok(TextToken::Wait(parse_decimal(value)?)),
+        "~" => Ok(TextToken::Null(parse_decimal(value)?)),
+        "CHAR" => Ok(TextToken::Raw(parse_hex(value)?)),
+        "VAR" => {
+            let value = value.trim();
+            match value.find('(') {
+                None => Ok(TextToken::Variable { code: parse_hex(value)?, args: vec![] }),
+                Some(start) => {
+                    let args = value[start + 1..].strip_suffix(')').ok_or_else(|| invalid_markup(format!("unterminated variable arguments in {:?}", command)))?;
+
+                    Ok(TextToken::Variable {
+                        code: parse_hex(&value[..start])?,
+                        args: args.split(',').map(parse_hex).collect::<Result<_, _>>()?,
+                    })
+                },
+            }
+        },
+        _ => Err(invalid_markup(format!("unknown command {:?}", command))),
+    }
+}
+
+// Inverse of `render`.
+pub fn parse(markup: &str) -> Result<Vec<TextToken>, Error> {
+    let mut tokens = vec![];
+    let mut text = String::new();
+
+    let mut chars = markup.chars();
+    while let Some(c) = chars.next() {
+        let token = match c {
+            '\\' => match chars.next() {
+                Some('n') => { text.push('\n'); None },
+                Some('\\') => { text.push('\\'); None },
+                Some('[') => { text.push('['); None },
+                Some('r') => Some(TextToken::Return),
+                Some('c') => Some(TextToken::Clear),
+                other => { return Err(invalid_markup(format!("unknown escape sequence \\{}", other.map(String::from).unwrap_or_default()))); },
+            },
+            '[' => {
+                let mut command = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(c) => command.push(c),
+                        None => { return Err(invalid_markup(format!("unterminated command [{}", command))); },
+                    }
+                }
+                Some(parse_command(&command)?)
+            },
+            c => { text.push(c); None },
+        };
+
+        if let Some(token) = token {
+            if !text.is_empty() {
+                tokens.push(TextToken::Text(std::mem::take(&mut text)));
+            }
+            tokens.push(token);
+        }
+    }
+
+    if !text.is_empty() {
+        tokens.push(TextToken::Text(text));
+    }
+
+    Ok(tokens)
 }
@@ -0,0 +1,133 @@
+use super::super::error::Error;
+use byteorder::LittleEndian;
+use byteorder::ReadBytesExt;
+use byteorder::WriteBytesExt;
+use std::io::Read;
+use std::io::Write;
+use super::table::Record;
+
+// One entry of the trainer data GARC per trainer.
+#[derive(Debug, Default, Clone)]
+pub struct Trainer {
+    pub class: u8,
+    pub battle_type: u8,
+    pub pokemon_count: u8,
+    pub unknown0: u8,
+    pub items: [u16; 4],
+    pub ai_flags: u32,
+    pub heal: u8,
+    pub money: u8,
+    pub unknown1: u16,
+}
+
+impl Record for Trainer {
+    const SIZE: usize = 0x14;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error> {
+        let mut trainer = Self::default();
+
+        trainer.class = input.read_u8()?;
+        trainer.battle_type = input.read_u8()?;
+        trainer.pokemon_count = input.read_u8()?;
+        trainer.unknown0 = input.read_u8()?;
+        for item in trainer.items.iter_mut() {
+            *item = input.read_u16::<LittleEndian>()?;
+        }
+        trainer.ai_flags = input.read_u32::<LittleEndian>()?;
+        trainer.heal = input.read_u8()?;
+        trainer.money = input.read_u8()?;
+        trainer.unknown1 = input.read_u16::<LittleEndian>()?;
+
+        Ok(trainer)
+    }
+
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        output.write_u8(self.class)?;
+        output.write_u8(self.battle_type)?;
+        output.write_u8(self.pokemon_count)?;
+        output.write_u8(self.unknown0)?;
+        for item in self.items.iter() {
+            output.write_u16::<LittleEndian>(*item)?;
+        }
+        output.write_u32::<LittleEndian>(self.ai_flags)?;
+        output.write_u8(self.heal)?;
+        output.write_u8(self.money)?;
+        output.write_u16::<LittleEndian>(self.unknown1)?;
+
+        Ok(())
+    }
+}
+
+// The trainer team GARC has one entry per trainer, with pokemon_count of these.
+#[derive(Debug, Default, Clone)]
+pub struct TrainerPokemon {
+    pub gender: u8,
+    pub ability: u8,
+    pub nature: u8,
+    pub evs: [u8; 6],
+    pub ivs: [u8; 6],
+    pub shiny: bool,
+    pub unknown0: u16,
+    pub level: u16,
+    pub species: u16,
+    pub form: u16,
+    pub item: u16,
+    pub unknown1: u16,
+    pub moves: [u16; 4],
+}
+
+impl Record for TrainerPokemon {
+    const SIZE: usize = 0x20;
+
+    fn read(input: &mut dyn Read) -> Result<Self, Error> {
+        let mut pokemon = Self::default();
+
+        let flags = input.read_u8()?;
+        pokemon.gender = flags & 0x3;
+        pokemon.ability = (flags >> 4) & 0x3;
+        pokemon.nature = input.read_u8()?;
+        input.read_exact(&mut pokemon.evs)?;
+
+        let ivs = input.read_u32::<LittleEndian>()?;
+        for (i, iv) in pokemon.ivs.iter_mut().enumerate() {
+            *iv = ((ivs >> (5 * i)) & 0x1F) as u8;
+        }
+        pokemon.shiny = (ivs >> 30) & 1 == 1;
+
+        pokemon.unknown0 = input.read_u16::<LittleEndian>()?;
+        pokemon.level = input.read_u16::<LittleEndian>()?;
+        pokemon.species = input.read_u16::<LittleEndian>()?;
+        pokemon.form = input.read_u16::<LittleEndian>()?;
+        pokemon.item = input.read_u16::<LittleEndian>()?;
+        pokemon.unknown1 = input.read_u16::<LittleEndian>()?;
+        for m in pokemon.moves.iter_mut() {
+            *m = input.read_u16::<LittleEndian>()?;
+        }
+
+        Ok(pokemon)
+    }
+
+    fn write(&self, output: &mut dyn Write) -> Result<(), Error> {
+        output.write_u8((self.gender & 0x3) | ((self.ability & 0x3) << 4))?;
+        output.write_u8(self.nature)?;
+        output.write_all(&self.evs)?;
+
+        let mut ivs = if self.shiny { 1u32 << 30 } else { 0 };
+        for (i, iv) in self.ivs.iter().enumerate() {
+            ivs |= (*iv as u32 & 0x1F) << (5 * i);
+        }
+        output.write_u32::<LittleEndian>(ivs)?;
+
+        output.write_u16::<LittleEndian>(self.unknown0)?;
+        output.write_u16::<LittleEndian>(self.level)?;
+        output.write_u16::<LittleEndian>(self.species)?;
+        output.write_u16::<LittleEndian>(self.form)?;
+        output.write_u16::<LittleEndian>(self.item)?;
+        output.write_u16::<LittleEndian>(self.unknown1)?;
+        for m in self.moves.iter() {
+            output.write_u16::<LittleEndian>(*m)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,20 +1,171 @@
 use std::io::SeekFrom;
-use std::io::Error;
+use super::error::Error;
 use std::io::ErrorKind;
+use std::io::Read;
 use std::io::Seek;
+use std::convert::TryFrom;
 
-#[derive(Debug, Clone)]
+// Where a Reader's bytes come from. Reads are positional, so any number of
+// readers, on any number of threads, can share one source without stepping on
+// each other's position.
+pub trait Source: Send + Sync {
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize, std::io::Error>;
+    fn length(&self) -> Result<u64, std::io::Error>;
+
+    // Sources that live in memory can hand out their contents directly.
+    fn bytes(&self) -> Option<&[u8]> {
+        None
+    }
+}
+
+// Files are read with pread (or its Windows equivalent), which doesn't need
+// a lock or the file's own position.
+impl Source for std::fs::File {
+    #[cfg(unix)]
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+        std::os::unix::fs::FileExt::read_at(self, buffer, offset)
+    }
+
+    #[cfg(windows)]
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+        std::os::windows::fs::FileExt::seek_read(self, buffer, offset)
+    }
+
+    fn length(&self) -> Result<u64, std::io::Error> {
+        Ok(self.metadata()?.len())
+    }
+}
+
+// Any other stream that can seek: decrypted or decompressed data, a Cursor,
+// etc. Every read locks it and seeks first.
+impl<T: Read + Seek + Send> Source for std::sync::Mutex<T> {
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+        let mut f = self.lock().map_err(|_e| std::io::Error::other("Lock failed"))?;
+
+        f.seek(SeekFrom::Start(offset))?;
+        f.read(buffer)
+    }
+
+    fn length(&self) -> Result<u64, std::io::Error> {
+        let mut f = self.lock().map_err(|_e| std::io::Error::other("Lock failed"))?;
+
+        f.seek(SeekFrom::End(0))
+    }
+}
+
+impl Source for Vec<u8> {
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+        Ok(read_slice(self, offset, buffer))
+    }
+
+    fn length(&self) -> Result<u64, std::io::Error> {
+        Ok(self.len() as u64)
+    }
+
+    fn bytes(&self) -> Option<&[u8]> {
+        Some(self)
+    }
+}
+
+impl Source for super::mmap::Mmap {
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+        Ok(read_slice(self, offset, buffer))
+    }
+
+    fn length(&self) -> Result<u64, std::io::Error> {
+        Ok(self.len() as u64)
+    }
+
+    fn bytes(&self) -> Option<&[u8]> {
+        Some(self)
+    }
+}
+
+fn read_slice(bytes: &[u8], offset: u64, buffer: &mut [u8]) -> usize {
+    let start = (offset as usize).min(bytes.len());
+    let count = buffer.len().min(bytes.len() - start);
+
+    buffer[..count].copy_from_slice(&bytes[start..start + count]);
+
+    count
+}
+
+// Byte slices are kept apart from other sources so they can be read without
+// going through a trait object. Shared sources are owned by every reader
+// that uses them, which makes for readers (and containers) that are 'static.
+#[derive(Clone)]
+enum Backing<'a> {
+    Source(&'a dyn Source),
+    Bytes(&'a [u8]),
+    Shared(std::sync::Arc<dyn Source>),
+}
+
+impl<'a> Backing<'a> {
+    fn read_at(&self, offset: u64, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+        match self {
+            Backing::Source(source) => source.read_at(offset, buffer),
+            Backing::Bytes(bytes) => Ok(read_slice(bytes, offset, buffer)),
+            Backing::Shared(source) => source.read_at(offset, buffer),
+        }
+    }
+
+    fn bytes(&self) -> Option<&[u8]> {
+        match self {
+            Backing::Source(source) => source.bytes(),
+            Backing::Bytes(bytes) => Some(bytes),
+            Backing::Shared(source) => source.bytes(),
+        }
+    }
+}
+
+// A reader that owns its source through an Arc, see `FileHolder::into_shared`.
+// Containers opened from one, like `ncsd::OwnedNCSD`, can be stored and sent
+// anywhere.
+pub type OwnedReader = Reader<'static>;
+
+#[derive(Clone)]
 pub struct Reader<'a> {
-    file: &'a std::sync::RwLock<std::fs::File>,
+    file: Backing<'a>,
     offset: u64,
     length: u64,
     position: u64,
 }
 
+impl<'a> std::fmt::Debug for Reader<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Reader")
+            .field("offset", &self.offset)
+            .field("length", &self.length)
+            .field("position", &self.position)
+            .finish()
+    }
+}
+
 impl<'a> Reader<'a> {
-    pub fn new(file: &'a std::sync::RwLock<std::fs::File>, offset: u64, length: u64) -> Reader<'a> {
+    // Reads from an in-memory buffer, like a decompressed file or a test fixture.
+    pub fn from_bytes(bytes: &'a [u8]) -> Reader<'a> {
         Reader {
-            file,
+            file: Backing::Bytes(bytes),
+            offset: 0,
+            length: bytes.len() as u64,
+            position: 0,
+        }
+    }
+
+    pub fn shared(file: std::sync::Arc<dyn Source>) -> Result<OwnedReader, Error> {
+        let length = file.length()?;
+
+        Ok(Reader {
+            file: Backing::Shared(file),
+            offset: 0,
+            length,
+            position: 0,
+        })
+    }
+
+    pub fn new(file: &'a dyn Source, offset: u64, length: u64) -> Reader<'a> {
+        Reader {
+            file: Backing::Source(file),
             offset,
             length,
             position: 0,
@@ -22,15 +173,14 @@ impl<'a> Reader<'a> {
     }
 
     pub fn limit(&self, offset: u64, length: u64) -> Result<Reader<'a>, Error> {
-        if offset + length <= self.length {
-            Ok(Reader {
-                file: self.file,
-                offset: self.offset + offset,
+        match (offset.checked_add(length), self.offset.checked_add(offset)) {
+            (Some(end), Some(absolute)) if end <= self.length => Ok(Reader {
+                file: self.file.clone(),
+                offset: absolute,
                 length: length,
                 position: 0,
-            })
-        } else {
-            Err(Error::new(ErrorKind::Other, "Reader limit out of bounds"))
+            }),
+            _ => Err(Error::out_of_bounds("reader", offset, length, self.length)),
         }
     }
 
@@ -46,17 +196,51 @@ impl<'a> Reader<'a> {
     pub fn length(&self) -> u64 {
         self.length
     }
+
+    // A reader for everything from offset to the end, without touching this
+    // one's position.
+    pub fn at(&self, offset: u64) -> Result<Reader<'a>, Error> {
+        self.limit(offset, self.length.saturating_sub(offset))
+    }
+
+    // The whole reader's contents without copying, when it's backed by memory.
+    pub fn bytes(&self) -> Option<&[u8]> {
+        let start = usize::try_from(self.offset).ok()?;
+        let end = start.checked_add(usize::try_from(self.length).ok()?)?;
+
+        self.file.bytes()?.get(start..end)
+    }
+
+    // Positional read that leaves the position alone.
+    pub fn read_exact_at(&self, offset: u64, buffer: &mut [u8]) -> Result<(), Error> {
+        if offset.checked_add(buffer.len() as u64).is_none_or(|end| end > self.length) {
+            return Err(Error::out_of_bounds("read", offset, buffer.len() as u64, self.length));
+        }
+
+        if let Some(bytes) = self.bytes() {
+            buffer.copy_from_slice(&bytes[offset as usize..offset as usize + buffer.len()]);
+            return Ok(());
+        }
+
+        self.limit(offset, buffer.len() as u64)?.read_exact(buffer)?;
+
+        Ok(())
+    }
 }
 
 impl<'a> std::io::Read for Reader<'a> {
-    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Error> {
-        let mut f = self.file.write().map_err(|_e| Error::new(ErrorKind::Other, "Write lock failed"))?;
+    // Like a Cursor, reading at or past the end gives 0 bytes.
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, std::io::Error> {
+        if self.position >= self.length {
+            return Ok(0);
+        }
 
-        f.seek(SeekFrom::Start(self.offset + self.position))?;
+        let maxread = buffer.len().min((self.length - self.position).min(usize::MAX as u64) as usize);
 
-        let maxread = buffer.len().min((self.length - self.position) as usize);
+        let at = self.offset.checked_add(self.position)
+            .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidInput, "read position overflows"))?;
 
-        let bytes_read = f.read(&mut buffer[0..maxread])?;
+        let bytes_read = self.file.read_at(at, &mut buffer[0..maxread])?;
         self.position += bytes_read as u64;
 
         Ok(bytes_read)
@@ -64,73 +248,111 @@ impl<'a> std::io::Read for Reader<'a> {
 }
 
 impl<'a> std::io::Seek for Reader<'a> {
-    fn seek(&mut self, destination: SeekFrom) -> Result<u64, Error> {
-        match destination {
-            SeekFrom::End(offset) => {
-                if offset > 0 {
-                    Err(Error::new(ErrorKind::Other, "Can't seek past end of file"))
-                } else {
-                    let offset = (-offset) as u64;
-                    if offset < self.length {
-                        self.position = self.length - offset;
-                        Ok(self.position)
-                    } else {
-                        Err(Error::new(ErrorKind::Other, "Can't seek before start of file"))
-                    }
-                }
-            },
-            SeekFrom::Start(offset) => {
-                if offset < self.length {
-                    self.position = offset;
-                    Ok(self.position)
-                } else {
-                    Err(Error::new(ErrorKind::Other, "Can't seek past end of file"))
-                }
+    // Follows std: any position from 0 up is fine, including the end and past
+    // it. Only going below 0 (or past u64::MAX) is an error.
+    fn seek(&mut self, destination: SeekFrom) -> Result<u64, std::io::Error> {
+        let position = match destination {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.length.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+
+        match position {
+            Some(position) => {
+                self.position = position;
+                Ok(position)
             },
-            SeekFrom::Current(offset) => {
-                if offset < 0 {
-                    let offset = (-offset) as u64;
-                    if self.position < offset {
-                        Err(Error::new(ErrorKind::Other, "Can't seek before start of file"))
-                    } else {
-                        self.position -= offset;
-                        Ok(self.position)
-                    }
-                } else {
-                    let offset = offset as u64;
-                    if self.position + offset < self.length {
-                        self.position += offset;
-                        Ok(self.position)
-                    } else {
-                        Err(Error::new(ErrorKind::Other, "Can't seek past end of file"))
-                    }
-                }
-            }
+            None => Err(std::io::Error::new(ErrorKind::InvalidInput, "invalid seek to a negative or overflowing position")),
         }
     }
 }
 
 pub trait VirtualFile<'a> {
-    fn reader(&self) -> Reader<'a>;
+    fn reader(&self) -> Result<Reader<'a>, Error>;
+}
+
+enum Holder {
+    Source(Box<dyn Source>),
+    Bytes(Vec<u8>),
+    Mapped(super::mmap::Mmap),
 }
 
+// Owns the source that readers borrow from.
 pub struct FileHolder {
-    file: std::sync::RwLock<std::fs::File>,
+    file: Holder,
     length: u64,
 }
 
 impl FileHolder {
-    pub fn new(mut file: std::fs::File) -> Result<Self, std::io::Error> {
-        let length = file.seek(std::io::SeekFrom::End(0))?;
-
-        Ok(FileHolder { file: file.into(), length })
+    pub fn new(file: std::fs::File) -> Result<Self, Error> {
+        Self::from_source(Box::new(file))
     }
 
-    pub fn open(filename: &str) -> Result<Self, std::io::Error> {
+    // "-" reads stdin.
+    pub fn open(filename: &str) -> Result<Self, Error> {
+        if filename == "-" {
+            return Self::read_all(std::io::stdin());
+        }
+
         Self::new(std::fs::File::open(filename)?)
     }
 
+    // Maps the file into memory instead of reading through it. Readers then
+    // hand out slices of the file directly, see `Reader::bytes`.
+    pub fn map(filename: &str) -> Result<Self, Error> {
+        let map = super::mmap::Mmap::new(&std::fs::File::open(filename)?)?;
+        let length = map.len() as u64;
+
+        Ok(FileHolder { file: Holder::Mapped(map), length })
+    }
+
+    pub fn from_stream<T: Read + Seek + Send + 'static>(stream: T) -> Result<Self, Error> {
+        Self::from_source(Box::new(std::sync::Mutex::new(stream)))
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        let length = bytes.len() as u64;
+
+        FileHolder { file: Holder::Bytes(bytes), length }
+    }
+
+    // For streams that can't seek, like stdin or a pipe: everything is read
+    // into memory first.
+    pub fn read_all<T: Read>(mut input: T) -> Result<Self, Error> {
+        let mut bytes = vec![];
+        input.read_to_end(&mut bytes)?;
+
+        Ok(Self::from_bytes(bytes))
+    }
+
+    pub fn from_source(file: Box<dyn Source>) -> Result<Self, Error> {
+        let length = file.length()?;
+
+        Ok(FileHolder { file: Holder::Source(file), length })
+    }
+
     pub fn reader<'a>(&'a self) -> Reader<'a> {
-        Reader::new(&self.file, 0, self.length)
+        match &self.file {
+            Holder::Source(source) => Reader::new(source.as_ref(), 0, self.length),
+            Holder::Bytes(bytes) => Reader::from_bytes(bytes),
+            Holder::Mapped(map) => Reader::from_bytes(map),
+        }
+    }
+
+    // Gives up the holder for a reader that owns the data, to open containers
+    // that aren't tied to a borrow.
+    pub fn into_shared(self) -> OwnedReader {
+        let source: std::sync::Arc<dyn Source> = match self.file {
+            Holder::Source(source) => source.into(),
+            Holder::Bytes(bytes) => std::sync::Arc::new(bytes),
+            Holder::Mapped(map) => std::sync::Arc::new(map),
+        };
+
+        Reader {
+            file: Backing::Shared(source),
+            offset: 0,
+            length: self.length,
+            position: 0,
+        }
     }
 }
@@ -1,6 +1,8 @@
+use super::error::Error;
 use byteorder::LittleEndian;
 use byteorder::ByteOrder;
 use byteorder::ReadBytesExt;
+use std::collections::HashSet;
 use std::io::SeekFrom;
 use std::io::Seek;
 use std::io::Read;
@@ -13,11 +15,16 @@ pub struct RomFS<'a> {
     lvl3_header: Level3Header,
 }
 
+pub type OwnedRomFS = RomFS<'static>;
+
 impl<'a> RomFS<'a> {
-    pub fn new(mut file: Reader<'a>) -> Result<RomFS, std::io::Error> {
+    pub fn new(mut file: Reader<'a>) -> Result<RomFS, Error> {
         let header = Header::read(&mut file)?;
 
-        let lvl3_header_offset = align(header.master_hash_size as u64 + align(header.header_length as u64, 0x10), header.level1_block_size);
+        let lvl3_header_offset = align(header.header_length as u64, 0x10)
+            .and_then(|length| length.checked_add(header.master_hash_size as u64))
+            .and_then(|offset| align(offset, header.level1_block_size))
+            .ok_or_else(|| Error::malformed("RomFS level 3 offset overflows"))?;
         file.seek(SeekFrom::Start(lvl3_header_offset))?;
 
         let mut lvl3_header = Level3Header::read(&mut file)?;
@@ -27,37 +34,44 @@ impl<'a> RomFS<'a> {
     }
 
     pub fn entries(&self) -> NodeIterator<'a> {
+        let lvl3 = &self.lvl3_header;
+
         NodeIterator {
             context: NodeIteratorContext {
                 file: self.file.clone(),
-                directory_base_offset: self.lvl3_header.header_offset + self.lvl3_header.directory_metadata_table.offset as u64,
-                file_base_offset: self.lvl3_header.header_offset + self.lvl3_header.file_metadata_table.offset as u64,
-                file_data_offset: self.lvl3_header.header_offset + self.lvl3_header.file_data_offset as u64,
+                directory_base_offset: lvl3.header_offset + lvl3.directory_metadata_table.offset as u64,
+                file_base_offset: lvl3.header_offset + lvl3.file_metadata_table.offset as u64,
+                file_data_offset: lvl3.header_offset + lvl3.file_data_offset as u64,
+                directory_hash_table: HashTable::new(lvl3.header_offset, &lvl3.directory_hash_table),
+                file_hash_table: HashTable::new(lvl3.header_offset, &lvl3.file_hash_table),
+                max_directories: lvl3.directory_metadata_table.length / DirectoryMetadataHeader::MIN_SIZE,
+                max_files: lvl3.file_metadata_table.length / FileMetadataHeader::MIN_SIZE,
             },
             next_directory: Some(0),
             next_file: None,
+            directories_read: 0,
+            files_read: 0,
+        }
+    }
+
+    pub fn root(&self) -> Result<DirectoryMetadata<'a>, Error> {
+        match self.entries().next()? {
+            Some(Node::Directory(root)) => Ok(root),
+            _ => Err(Error::not_found("RomFS root directory")),
         }
     }
 
-    pub fn file_at(&self, path: &str) -> Result<Option<Node<'a>>, std::io::Error> {
-        let mut context = self.entries().next()?.ok_or(std::io::Error::new(std::io::ErrorKind::Other, "Couldn't find root directory"))?;
+    // Empty components are skipped, so "" is the root and "a/0/" is "a/0".
+    pub fn file_at(&self, path: &str) -> Result<Option<Node<'a>>, Error> {
+        let mut context = Node::Directory(self.root()?);
 
-        for component in path.split("/") {
+        for component in path.split('/').filter(|component| !component.is_empty()) {
             let directory = match context {
                 Node::Directory(dir) => dir,
                 Node::File(_) => { return Ok(None); },
             };
 
-            let mut found = None;
-            let mut it = directory.entries();
-            while let Some(entry) = it.next()? {
-                if entry.basename() == component {
-                    found = Some(entry);
-                    break;
-                }
-            }
-
-            match found {
+            match directory.child(component)? {
                 None => { return Ok(None) },
                 Some(entry) => { context = entry; },
             }
@@ -65,11 +79,104 @@ impl<'a> RomFS<'a> {
 
         Ok(Some(context))
     }
+
+    pub fn dir_at(&self, path: &str) -> Result<Option<DirectoryMetadata<'a>>, Error> {
+        match self.file_at(path)? {
+            Some(Node::Directory(dir)) => Ok(Some(dir)),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn exists(&self, path: &str) -> Result<bool, Error> {
+        Ok(self.file_at(path)?.is_some())
+    }
+
+    // Every node below the root, depth first, with its full path.
+    pub fn walk(&self) -> Result<Walk<'a>, Error> {
+        Ok(self.root()?.walk())
+    }
+
+    // Nodes whose path matches pattern, like "a/0/3/*". "*" and "?" only
+    // match within one component.
+    pub fn glob(&self, pattern: &str) -> Result<Vec<(String, Node<'a>)>, Error> {
+        let components = pattern.split('/').filter(|component| !component.is_empty()).collect::<Vec<_>>();
+        let mut found = vec![];
+
+        glob_directory(&self.root()?, &components, "", &mut found)?;
+
+        Ok(found)
+    }
+}
+
+fn glob_directory<'a>(directory: &DirectoryMetadata<'a>, components: &[&str], prefix: &str, found: &mut Vec<(String, Node<'a>)>) -> Result<(), Error> {
+    let (component, rest) = match components.split_first() {
+        Some(split) => split,
+        None => { return Ok(()); },
+    };
+
+    let mut matches = vec![];
+    if component.contains(['*', '?']) {
+        let mut it = directory.entries();
+        while let Some(entry) = it.next()? {
+            if glob_matches(component, entry.basename()) {
+                matches.push(entry);
+            }
+        }
+    } else if let Some(entry) = directory.child(component)? {
+        matches.push(entry);
+    }
+
+    for entry in matches {
+        let path = join(prefix, entry.basename());
+
+        match (rest.is_empty(), entry) {
+            (true, entry) => found.push((path, entry)),
+            (false, Node::Directory(dir)) => glob_directory(&dir, rest, &path, found)?,
+            (false, Node::File(_)) => {},
+        }
+    }
+
+    Ok(())
+}
+
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+
+    // Where to resume after the last "*": (pattern index, name index).
+    let mut star = None;
+    let (mut p, mut n) = (0, 0);
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            star = Some((star_p, star_n + 1));
+            p = star_p + 1;
+            n = star_n + 1;
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn join(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", prefix, name)
+    }
 }
 
 impl<'a> super::read::VirtualFile<'a> for RomFS<'a> {
-    fn reader(&self) -> Reader<'a> {
-        self.file.at_zero()
+    fn reader(&self) -> Result<Reader<'a>, Error> {
+        Ok(self.file.at_zero())
     }
 }
 
@@ -95,29 +202,33 @@ struct Header {
 }
 
 impl Header {
-    pub fn read(input: &mut Reader) -> Result<Self, std::io::Error> {
+    pub fn read(input: &mut Reader) -> Result<Self, Error> {
         let mut header = Self::default();
 
         input.read_exact(&mut header.magic)?;
-        assert!(header.magic == *b"IVFC");
+        if header.magic != *b"IVFC" {
+            return Err(Error::bad_magic(b"IVFC", &header.magic, 0));
+        }
 
         header.bom = input.read_u32::<LittleEndian>()?;
-        assert!(header.bom == 0x10000);
+        if header.bom != 0x10000 {
+            return Err(Error::UnsupportedVersion { what: "IVFC", version: header.bom as u64 });
+        }
         header.master_hash_size = input.read_u32::<LittleEndian>()?;
 
         header.level1_logical_offset = input.read_u64::<LittleEndian>()?;
         header.level1_hashdata_size = input.read_u64::<LittleEndian>()?;
-        header.level1_block_size = 2u64.pow(input.read_u32::<LittleEndian>()?);
+        header.level1_block_size = block_size(input.read_u32::<LittleEndian>()?)?;
         header.reserved0 = input.read_u32::<LittleEndian>()?;
 
         header.level2_logical_offset = input.read_u64::<LittleEndian>()?;
         header.level2_hashdata_size = input.read_u64::<LittleEndian>()?;
-        header.level2_block_size = 2u64.pow(input.read_u32::<LittleEndian>()?);
+        header.level2_block_size = block_size(input.read_u32::<LittleEndian>()?)?;
         header.reserved1 = input.read_u32::<LittleEndian>()?;
 
         header.level3_logical_offset = input.read_u64::<LittleEndian>()?;
         header.level3_hashdata_size = input.read_u64::<LittleEndian>()?;
-        header.level3_block_size = 2u64.pow(input.read_u32::<LittleEndian>()?);
+        header.level3_block_size = block_size(input.read_u32::<LittleEndian>()?)?;
         header.reserved2 = input.read_u32::<LittleEndian>()?;
         header.header_length = input.read_u32::<LittleEndian>()?;
         header.reserved3 = input.read_u32::<LittleEndian>()?;
@@ -140,6 +251,60 @@ impl<'a> Node<'a> {
             Self::Directory(d) => d.basename(),
         }
     }
+
+    pub fn path(&self) -> Result<String, Error> {
+        match self {
+            Self::File(f) => f.path(),
+            Self::Directory(d) => d.path(),
+        }
+    }
+}
+
+pub struct Walk<'a> {
+    // The directories being walked, innermost last, with their paths.
+    stack: Vec<(String, NodeIterator<'a>)>,
+    // Every directory entered so far. A tree never has one twice.
+    visited: HashSet<u32>,
+}
+
+impl<'a> Iterator for Walk<'a> {
+    type Item = Result<(String, Node<'a>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.try_next() {
+            Ok(next) => next.map(Ok),
+            Err(e) => {
+                self.stack.clear();
+                Some(Err(e))
+            },
+        }
+    }
+}
+
+impl<'a> Walk<'a> {
+    pub fn try_next(&mut self) -> Result<Option<(String, Node<'a>)>, Error> {
+        while let Some((prefix, entries)) = self.stack.last_mut() {
+            let entry = match entries.next()? {
+                Some(entry) => entry,
+                None => {
+                    self.stack.pop();
+                    continue;
+                },
+            };
+
+            let path = join(prefix, entry.basename());
+            if let Node::Directory(dir) = &entry {
+                if !self.visited.insert(dir.offset) {
+                    return Err(Error::malformed(format!("RomFS directory {} is its own ancestor", path)));
+                }
+                self.stack.push((path.clone(), dir.entries()));
+            }
+
+            return Ok(Some((path, entry)));
+        }
+
+        Ok(None)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -148,26 +313,58 @@ pub struct NodeIteratorContext<'a> {
     directory_base_offset: u64,
     file_base_offset: u64,
     file_data_offset: u64,
+    directory_hash_table: HashTable,
+    file_hash_table: HashTable,
+    // No list or hash chain can be longer than its metadata table has entries.
+    max_directories: u32,
+    max_files: u32,
+}
+
+// Buckets of u32 offsets into a metadata table, chained through each entry's
+// hash_next_directory/hash_next_file.
+#[derive(Clone, Copy, Debug)]
+struct HashTable {
+    offset: u64,
+    buckets: u32,
+}
+
+impl HashTable {
+    fn new(header_offset: u64, section: &Level3HeaderSection) -> Self {
+        HashTable { offset: header_offset + section.offset as u64, buckets: section.length / 4 }
+    }
+
+    fn first(&self, file: &Reader, parent: u32, name: &str) -> Result<Option<u32>, Error> {
+        if self.buckets == 0 {
+            return Ok(None);
+        }
+
+        let bucket = hash(parent, name) % self.buckets;
+        read_option_u32(&mut file.at(self.offset + bucket as u64 * 4)?)
+    }
+}
+
+fn hash(parent: u32, name: &str) -> u32 {
+    name.encode_utf16().fold(parent ^ 123456789, |hash, unit| hash.rotate_right(5) ^ unit as u32)
 }
 
 pub struct NodeIterator<'a> {
     context: NodeIteratorContext<'a>,
     next_directory: Option<u32>,
     next_file: Option<u32>,
+    // Entries returned so far, to catch lists that loop.
+    directories_read: u32,
+    files_read: u32,
 }
 
 impl<'a> NodeIterator<'a> {
-    fn directory_offset(&self) -> Option<u64> {
-        self.next_directory.map(|offset| self.context.directory_base_offset + offset as u64)
-    }
-
-    fn file_offset(&self) -> Option<u64> {
-        self.next_file.map(|offset| self.context.file_base_offset + offset as u64)
-    }
-
-    pub fn next(&mut self) -> Result<Option<Node<'a>>, std::io::Error> {
-        match self.directory_offset() {
+    pub fn next(&mut self) -> Result<Option<Node<'a>>, Error> {
+        match self.next_directory {
             Some(offset) => {
+                if self.directories_read >= self.context.max_directories {
+                    return Err(Error::malformed("RomFS subdirectory list loops"));
+                }
+                self.directories_read += 1;
+
                 match Self::read_directory(&self.context, offset) {
                     Ok(dm) => {
                         self.next_directory = dm.header.next_directory;
@@ -176,8 +373,13 @@ impl<'a> NodeIterator<'a> {
                     Err(e) => Err(e),
                 }
             },
-            None => match self.file_offset() {
+            None => match self.next_file {
                 Some(offset) => {
+                    if self.files_read >= self.context.max_files {
+                        return Err(Error::malformed("RomFS file list loops"));
+                    }
+                    self.files_read += 1;
+
                     match Self::read_file(&self.context, offset) {
                         Ok(fm) => {
                             self.next_file = fm.header.next_file;
@@ -191,20 +393,15 @@ impl<'a> NodeIterator<'a> {
         }
     }
 
-    fn read_directory<'b>(context: &NodeIteratorContext<'b>, offset: u64) -> Result<DirectoryMetadata<'b>, std::io::Error> {
-        let mut file = context.file.clone();
-        file.seek(std::io::SeekFrom::Start(offset))?;
+    // Offsets are relative to the start of their metadata table.
+    fn read_directory<'b>(context: &NodeIteratorContext<'b>, offset: u32) -> Result<DirectoryMetadata<'b>, Error> {
+        let header = DirectoryMetadataHeader::read(&mut context.file.at(context.directory_base_offset + offset as u64)?)?;
 
-        let header = DirectoryMetadataHeader::read(&mut file)?;
-
-        Ok(DirectoryMetadata { context: context.clone(), header })
+        Ok(DirectoryMetadata { context: context.clone(), offset, header })
     }
 
-    pub fn read_file<'b>(context: &NodeIteratorContext<'b>, offset: u64) -> Result<FileMetadata<'b>, std::io::Error> {
-        let mut file = context.file.clone();
-        file.seek(std::io::SeekFrom::Start(offset))?;
-
-        let header = FileMetadataHeader::read(&mut file)?;
+    pub fn read_file<'b>(context: &NodeIteratorContext<'b>, offset: u32) -> Result<FileMetadata<'b>, Error> {
+        let header = FileMetadataHeader::read(&mut context.file.at(context.file_base_offset + offset as u64)?)?;
 
         Ok(FileMetadata { context: context.clone(), header })
     }
@@ -229,7 +426,7 @@ pub struct Level3Header {
 }
 
 impl Level3Header {
-    pub fn read(input: &mut Reader) -> Result<Self, std::io::Error> {
+    pub fn read(input: &mut Reader) -> Result<Self, Error> {
         let mut lvl3 = Self::default();
 
         lvl3.header_length = input.read_u32::<LittleEndian>()?;
@@ -247,17 +444,23 @@ impl Level3Header {
     }
 }
 
-fn align(offset: u64, block_size: u64) -> u64 {
+fn align(offset: u64, block_size: u64) -> Option<u64> {
     if offset % block_size == 0 {
-        offset
+        Some(offset)
     } else {
-        offset + (block_size - (offset % block_size))
+        offset.checked_add(block_size - (offset % block_size))
     }
 }
 
+// Stored as a power of two.
+fn block_size(log2: u32) -> Result<u64, Error> {
+    1u64.checked_shl(log2).ok_or(Error::UnsupportedVersion { what: "IVFC block size", version: log2 as u64 })
+}
+
 #[derive(Debug, Clone)]
 pub struct DirectoryMetadata<'a> {
     context: NodeIteratorContext<'a>,
+    offset: u32,
     header: DirectoryMetadataHeader,
 }
 
@@ -266,13 +469,113 @@ impl<'a> DirectoryMetadata<'a> {
         &self.header.basename
     }
 
+    pub fn is_root(&self) -> bool {
+        self.offset == 0
+    }
+
+    pub fn parent(&self) -> Result<Option<DirectoryMetadata<'a>>, Error> {
+        if self.is_root() {
+            Ok(None)
+        } else {
+            NodeIterator::read_directory(&self.context, self.header.parent).map(Some)
+        }
+    }
+
+    // Relative to the root, which is "".
+    pub fn path(&self) -> Result<String, Error> {
+        let mut names = vec![];
+        let mut visited = vec![];
+        let mut directory = self.clone();
+
+        while !directory.is_root() {
+            if visited.contains(&directory.offset) {
+                return Err(Error::malformed("RomFS directory is its own ancestor"));
+            }
+            visited.push(directory.offset);
+
+            let parent = NodeIterator::read_directory(&directory.context, directory.header.parent)?;
+            names.push(std::mem::replace(&mut directory, parent).header.basename);
+        }
+
+        names.reverse();
+        Ok(names.join("/"))
+    }
+
+    // Everything below this directory, with paths relative to it.
+    pub fn walk(&self) -> Walk<'a> {
+        Walk {
+            stack: vec![(String::new(), self.entries())],
+            visited: std::iter::once(self.offset).collect(),
+        }
+    }
+
     pub fn entries(&self) -> NodeIterator<'a> {
         NodeIterator {
             context: self.context.clone(),
             next_directory: self.header.first_subdirectory,
             next_file: self.header.first_file,
+            directories_read: 0,
+            files_read: 0,
         }
     }
+
+    // Subdirectories win over files of the same name.
+    pub fn child(&self, name: &str) -> Result<Option<Node<'a>>, Error> {
+        if let Some(entry) = self.hashed_child(name)? {
+            return Ok(Some(entry));
+        }
+
+        // Hash tables can be missing or stale in rebuilt images, so a miss
+        // still walks the sibling lists.
+        let mut it = self.entries();
+        while let Some(entry) = it.next()? {
+            if entry.basename() == name {
+                return Ok(Some(entry));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn hashed_child(&self, name: &str) -> Result<Option<Node<'a>>, Error> {
+        let context = &self.context;
+
+        let mut next = context.directory_hash_table.first(&context.file, self.offset, name)?;
+        for _ in 0..context.max_directories {
+            let offset = match next {
+                Some(offset) => offset,
+                None => { break; },
+            };
+
+            let dir = NodeIterator::read_directory(context, offset)?;
+            if dir.header.parent == self.offset && dir.header.basename == name {
+                return Ok(Some(Node::Directory(dir)));
+            }
+            next = dir.header.hash_next_directory;
+        }
+        if next.is_some() {
+            return Err(Error::malformed("RomFS directory hash chain loops"));
+        }
+
+        let mut next = context.file_hash_table.first(&context.file, self.offset, name)?;
+        for _ in 0..context.max_files {
+            let offset = match next {
+                Some(offset) => offset,
+                None => { break; },
+            };
+
+            let file = NodeIterator::read_file(context, offset)?;
+            if file.header.parent == self.offset && file.header.basename == name {
+                return Ok(Some(Node::File(file)));
+            }
+            next = file.header.hash_next_file;
+        }
+        if next.is_some() {
+            return Err(Error::malformed("RomFS file hash chain loops"));
+        }
+
+        Ok(None)
+    }
 }
 
 
@@ -288,7 +591,10 @@ pub struct DirectoryMetadataHeader {
 }
 
 impl DirectoryMetadataHeader {
-    fn read(input: &mut Reader) -> Result<Self, std::io::Error> {
+    // An entry with an empty name.
+    const MIN_SIZE: u32 = 0x18;
+
+    fn read(input: &mut Reader) -> Result<Self, Error> {
         let mut header = DirectoryMetadataHeader::default();
 
         header.parent = input.read_u32::<LittleEndian>()?;
@@ -298,6 +604,10 @@ impl DirectoryMetadataHeader {
         header.hash_next_directory = read_option_u32(input)?;
         header.name_length = input.read_u32::<LittleEndian>()?;
 
+        if header.name_length as u64 > input.length() {
+            return Err(Error::out_of_bounds("RomFS name", 0, header.name_length as u64, input.length()));
+        }
+
         let mut buffer = vec![];
         buffer.resize(header.name_length as usize, 0);
         input.read_exact(&mut buffer)?;
@@ -308,7 +618,7 @@ impl DirectoryMetadataHeader {
     }
 }
 
-pub fn read_option_u32(input: &mut dyn std::io::Read) -> Result<Option<u32>, std::io::Error> {
+pub fn read_option_u32(input: &mut dyn std::io::Read) -> Result<Option<u32>, Error> {
     let offset = input.read_u32::<LittleEndian>()?;
 
     if offset == 0xFFFFFFFF {
@@ -318,11 +628,16 @@ pub fn read_option_u32(input: &mut dyn std::io::Read) -> Result<Option<u32>, std
     }
 }
 
-pub fn read_le16_string(input: &[u8]) -> Result<String, std::io::Error> {
+pub fn read_le16_string(input: &[u8]) -> Result<String, Error> {
     let mut s = String::new();
 
+    if !input.len().is_multiple_of(2) {
+        return Err(Error::malformed("RomFS name has an odd length"));
+    }
+
     for i in (0..input.len()).step_by(2) {
-        s.push(char::from_u32(LittleEndian::read_u16(&input[i..i+2]) as u32).unwrap());
+        let unit = LittleEndian::read_u16(&input[i..i+2]);
+        s.push(char::from_u32(unit as u32).ok_or_else(|| Error::malformed(format!("RomFS name has a lone surrogate {:#06x}", unit)))?);
     }
 
     Ok(s)
@@ -338,11 +653,26 @@ impl<'a> FileMetadata<'a> {
     pub fn basename(&self) -> &String {
         &self.header.basename
     }
+
+    pub fn size(&self) -> u64 {
+        self.header.file_data_length
+    }
+
+    pub fn parent(&self) -> Result<DirectoryMetadata<'a>, Error> {
+        NodeIterator::read_directory(&self.context, self.header.parent)
+    }
+
+    pub fn path(&self) -> Result<String, Error> {
+        Ok(join(&self.parent()?.path()?, self.basename()))
+    }
 }
 
 impl<'a> super::read::VirtualFile<'a> for FileMetadata<'a> {
-    fn reader(&self) -> Reader<'a> {
-        self.context.file.limit(self.context.file_data_offset + self.header.file_data_offset, self.header.file_data_length).unwrap()
+    fn reader(&self) -> Result<Reader<'a>, Error> {
+        let offset = self.context.file_data_offset.checked_add(self.header.file_data_offset)
+            .ok_or_else(|| Error::out_of_bounds("RomFS file", self.header.file_data_offset, self.header.file_data_length, self.context.file.length()))?;
+
+        self.context.file.limit(offset, self.header.file_data_length)
     }
 }
 
@@ -358,7 +688,9 @@ pub struct FileMetadataHeader {
 }
 
 impl FileMetadataHeader {
-    pub fn read(input: &mut Reader) -> Result<Self, std::io::Error> {
+    const MIN_SIZE: u32 = 0x20;
+
+    pub fn read(input: &mut Reader) -> Result<Self, Error> {
         let mut header = FileMetadataHeader::default();
 
         header.parent = input.read_u32::<LittleEndian>()?;
@@ -368,6 +700,10 @@ impl FileMetadataHeader {
         header.hash_next_file = read_option_u32(input)?;
         header.name_length = input.read_u32::<LittleEndian>()?;
 
+        if header.name_length as u64 > input.length() {
+            return Err(Error::out_of_bounds("RomFS name", 0, header.name_length as u64, input.length()));
+        }
+
         let mut buffer = vec![];
         buffer.resize(header.name_length as usize, 0);
         input.read_exact(&mut buffer)?;
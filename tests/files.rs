@@ -0,0 +1,33 @@
+use vgc_data::games::pokemon::export::TextKind;
+use vgc_data::games::pokemon::Game;
+use vgc_data::games::pokemon::Language;
+use vgc_data::games::pokemon::TextFile;
+
+#[test]
+fn text_archives_per_language() {
+    assert_eq!(Game::Sun.files().common_text(Language::English).unwrap(), "a/0/3/2");
+    assert_eq!(Game::Moon.files().story_text(Language::ChineseTraditional).unwrap(), "a/0/4/9");
+    assert_eq!(Game::X.files().common_text(Language::Korean).unwrap(), "a/0/7/7");
+    assert_eq!(TextKind::Story.archive(Game::AlphaSapphire, Language::Japanese).unwrap(), "a/0/7/7");
+}
+
+#[test]
+fn gen6_has_no_chinese_text() {
+    for game in &[Game::X, Game::Y, Game::OmegaRuby, Game::AlphaSapphire] {
+        for language in &[Language::ChineseSimplified, Language::ChineseTraditional] {
+            assert!(game.files().common_text(*language).is_err());
+            assert!(TextKind::Story.archive(*game, *language).is_err());
+        }
+    }
+}
+
+#[test]
+fn gen6_text_files_cover_items_and_moves() {
+    for game in &[Game::X, Game::Y, Game::OmegaRuby, Game::AlphaSapphire] {
+        for file in &[TextFile::ItemNames, TextFile::ItemDescriptions, TextFile::MoveNames, TextFile::MoveDescriptions] {
+            assert!(file.index(game).is_some(), "{:?} {:?}", game, file);
+        }
+
+        assert!(TextFile::ItemPluralNames.index(game).is_none());
+    }
+}
@@ -0,0 +1,213 @@
+use proptest::prelude::*;
+use std::io::Read;
+use vgc_data::garc::GARC;
+use vgc_data::read::Reader;
+use vgc_data::read::VirtualFile;
+
+// A version 0x400 GARC. Each entry lists its subentries as (bit, data).
+// FATB has to average 16 bytes per entry, so sparse entries need an empty
+// one next to them.
+fn build(entries: &[&[(u8, &[u8])]]) -> Vec<u8> {
+    let mut fato = vec![];
+    let mut fatb = vec![];
+    let mut data = vec![];
+
+    for entry in entries {
+        fato.extend(&(fatb.len() as u32).to_le_bytes());
+
+        let vector = entry.iter().fold(0u32, |vector, (bit, _)| vector | 1 << bit);
+        fatb.extend(&vector.to_le_bytes());
+        for (_, contents) in entry.iter() {
+            fatb.extend(&(data.len() as u32).to_le_bytes());
+            fatb.extend(&((data.len() + contents.len()) as u32).to_le_bytes());
+            fatb.extend(&(contents.len() as u32).to_le_bytes());
+            data.extend(*contents);
+        }
+    }
+
+    let data_offset = 0x1C + 12 + fato.len() + 12 + fatb.len() + 12;
+    let mut output = vec![];
+
+    output.extend(b"CRAG");
+    output.extend(&0x1Cu32.to_le_bytes());
+    output.extend(&0xFEFFu16.to_le_bytes());
+    output.extend(&0x400u16.to_le_bytes());
+    output.extend(&0u32.to_le_bytes());
+    output.extend(&(data_offset as u32).to_le_bytes());
+    output.extend(&((data_offset + data.len()) as u32).to_le_bytes());
+    output.extend(&0u32.to_le_bytes());
+
+    output.extend(b"OTAF");
+    output.extend(&(12 + fato.len() as u32).to_le_bytes());
+    output.extend(&(entries.len() as u16).to_le_bytes());
+    output.extend(&0xFFFFu16.to_le_bytes());
+    output.extend(&fato);
+
+    output.extend(b"BTAF");
+    output.extend(&(12 + fatb.len() as u32).to_le_bytes());
+    output.extend(&(entries.len() as u32).to_le_bytes());
+    output.extend(&fatb);
+
+    output.extend(b"BMIF");
+    output.extend(&12u32.to_le_bytes());
+    output.extend(&(data.len() as u32).to_le_bytes());
+    output.extend(&data);
+
+    output
+}
+
+fn sample() -> Vec<u8> {
+    build(&[
+        &[(0, b"a")],
+        &[(0, b"b0"), (2, b"b2")],
+        &[],
+        &[(1, b"d1")],
+    ])
+}
+
+fn contents(reader: Reader) -> Vec<u8> {
+    let mut reader = reader;
+    let mut buffer = vec![];
+    reader.read_to_end(&mut buffer).unwrap();
+    buffer
+}
+
+// Walks everything, the point is not panicking.
+fn read_all(data: &[u8]) -> Result<usize, vgc_data::error::Error> {
+    let garc = GARC::new(Reader::from_bytes(data))?;
+    let mut count = 0;
+
+    for entry in garc.entries() {
+        for subentry in entry?.entries() {
+            count += subentry?.reader()?.length() as usize;
+        }
+    }
+
+    Ok(count)
+}
+
+#[test]
+fn reads_the_fixture() {
+    let data = sample();
+    let garc = GARC::new(Reader::from_bytes(&data)).unwrap();
+
+    let all = garc.entries().map(|entry| {
+        entry.unwrap().entries().map(|subentry| contents(subentry.unwrap().reader().unwrap())).collect::<Vec<_>>()
+    }).collect::<Vec<_>>();
+
+    assert_eq!(all, vec![vec![b"a".to_vec()], vec![b"b0".to_vec(), b"b2".to_vec()], vec![], vec![b"d1".to_vec()]]);
+}
+
+#[test]
+fn fatb_smaller_than_its_header() {
+    let mut data = build(&[]);
+    // section size 0 and a file count whose size in bytes overflows a u32.
+    data[0x28 + 4..0x28 + 8].copy_from_slice(&0u32.to_le_bytes());
+    data[0x28 + 8..0x28 + 12].copy_from_slice(&0x1000_0000u32.to_le_bytes());
+
+    assert!(GARC::new(Reader::from_bytes(&data)).is_err());
+}
+
+#[test]
+fn entries_by_index() {
+    let data = sample();
+    let garc = GARC::new(Reader::from_bytes(&data)).unwrap();
+
+    assert_eq!(garc.len(), 4);
+    assert!(!garc.is_empty());
+    assert_eq!(garc.entry(3).unwrap().unwrap().index(), 3);
+    assert!(garc.entry(4).unwrap().is_none());
+    assert!(garc.entry(usize::MAX).unwrap().is_none());
+
+    let counts = (0..4).map(|i| garc.entry(i).unwrap().unwrap().subfile_count()).collect::<Vec<_>>();
+    assert_eq!(counts, [1, 2, 0, 1]);
+}
+
+#[test]
+fn subfiles_by_bit_index() {
+    let data = sample();
+    let garc = GARC::new(Reader::from_bytes(&data)).unwrap();
+    let subfile = |i, j| garc.entry(i).unwrap().unwrap().subfile(j).unwrap().map(|subentry| (subentry.index(), contents(subentry.reader().unwrap())));
+
+    assert_eq!(subfile(0, 0), Some((0, b"a".to_vec())));
+    assert_eq!(subfile(1, 0), Some((0, b"b0".to_vec())));
+    assert_eq!(subfile(1, 1), None);
+    assert_eq!(subfile(1, 2), Some((2, b"b2".to_vec())));
+    assert_eq!(subfile(2, 0), None);
+    assert_eq!(subfile(3, 0), None);
+    assert_eq!(subfile(3, 1), Some((1, b"d1".to_vec())));
+    assert_eq!(subfile(3, 32), None);
+}
+
+#[test]
+fn file_at_counts_existing_subfiles() {
+    let data = sample();
+    let garc = GARC::new(Reader::from_bytes(&data)).unwrap();
+    let file_at = |i, j| garc.file_at(i, j).unwrap().map(|subentry| (subentry.index(), contents(subentry.reader().unwrap())));
+
+    assert_eq!(file_at(1, 1), Some((2, b"b2".to_vec())));
+    assert_eq!(file_at(1, 2), None);
+    assert_eq!(file_at(3, 0), Some((1, b"d1".to_vec())));
+    assert_eq!(file_at(2, 0), None);
+    assert_eq!(file_at(4, 0), None);
+}
+
+#[test]
+fn iterators_know_their_length() {
+    let data = sample();
+    let garc = GARC::new(Reader::from_bytes(&data)).unwrap();
+
+    assert_eq!(garc.entries().size_hint(), (4, Some(4)));
+
+    let mut subentries = garc.entry(1).unwrap().unwrap().entries();
+    assert_eq!(subentries.size_hint(), (2, Some(2)));
+    subentries.next().unwrap().unwrap();
+    assert_eq!(subentries.size_hint(), (1, Some(1)));
+    assert_eq!(subentries.next().unwrap().unwrap().index(), 2);
+    assert!(subentries.next().is_none());
+}
+
+proptest! {
+    #[test]
+    fn corrupted_garcs_dont_panic(changes in prop::collection::vec((any::<usize>(), any::<u8>()), 1..8)) {
+        let mut data = sample();
+        for (index, value) in changes {
+            let len = data.len();
+            data[index % len] = value;
+        }
+
+        let _ = read_all(&data);
+    }
+
+    // Direct lookups have to agree with walking the entry.
+    #[test]
+    fn lookups_match_iteration(bits in prop::collection::btree_set(0u8..32, 1..8)) {
+        let data = bits.iter().map(|bit| vec![*bit; 3]).collect::<Vec<_>>();
+        let subentries = bits.iter().zip(&data).map(|(bit, data)| (*bit, data.as_slice())).collect::<Vec<_>>();
+
+        // k subentries need k - 1 empty entries for FATB to average 16 bytes.
+        let mut entries = vec![subentries.as_slice()];
+        entries.resize(bits.len(), &[]);
+        let data = build(&entries);
+
+        let garc = GARC::new(Reader::from_bytes(&data)).unwrap();
+        let entry = garc.entry(0).unwrap().unwrap();
+        prop_assert_eq!(entry.subfile_count() as usize, bits.len());
+
+        for (n, subentry) in entry.entries().enumerate() {
+            let subentry = subentry.unwrap();
+            let by_bit = entry.subfile(subentry.index() as usize).unwrap().unwrap();
+            let by_position = entry.nth_subfile(n).unwrap().unwrap();
+
+            prop_assert_eq!(by_bit.index(), subentry.index());
+            prop_assert_eq!(by_position.index(), subentry.index());
+            prop_assert_eq!(contents(by_bit.reader().unwrap()), vec![subentry.index(); 3]);
+        }
+    }
+
+    #[test]
+    fn truncated_garcs_dont_panic(length in 0usize..0x80) {
+        let data = sample();
+        let _ = read_all(&data[..length.min(data.len())]);
+    }
+}
@@ -0,0 +1,19 @@
+use vgc_data::ncsd::NCSD;
+use vgc_data::read::Reader;
+
+// Only the magic number is set, so every partition is unused.
+fn empty_ncsd() -> Vec<u8> {
+    let mut data = vec![0; 0x4000];
+    data[0x100..0x104].copy_from_slice(b"NCSD");
+    data
+}
+
+#[test]
+fn trailing_empty_partitions_end_iteration() {
+    let data = empty_ncsd();
+    let ncsd = NCSD::new(Reader::from_bytes(&data)).unwrap();
+
+    let mut partitions = ncsd.partitions();
+    assert!(partitions.next().unwrap().is_none());
+    assert!(partitions.next().unwrap().is_none());
+}
@@ -0,0 +1,176 @@
+use proptest::prelude::*;
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use vgc_data::read::FileHolder;
+use vgc_data::read::Reader;
+
+#[derive(Debug, Clone)]
+enum Op {
+    Seek(SeekFrom),
+    Read(usize),
+}
+
+fn seek_from() -> impl Strategy<Value = SeekFrom> {
+    prop_oneof![
+        (0u64..600).prop_map(SeekFrom::Start),
+        any::<u64>().prop_map(SeekFrom::Start),
+        (-600i64..600).prop_map(SeekFrom::End),
+        (-600i64..600).prop_map(SeekFrom::Current),
+        any::<i64>().prop_map(SeekFrom::Current),
+    ]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        seek_from().prop_map(Op::Seek),
+        (0usize..64).prop_map(Op::Read),
+    ]
+}
+
+// Data plus a slice of it, as (offset, length).
+fn data_and_slice() -> impl Strategy<Value = (Vec<u8>, u64, u64)> {
+    prop::collection::vec(any::<u8>(), 0..512).prop_flat_map(|data| {
+        let len = data.len() as u64;
+        (Just(data), 0..=len).prop_flat_map(move |(data, offset)| (Just(data), Just(offset), 0..=len - offset))
+    })
+}
+
+// A Reader should behave exactly like a Cursor over the same bytes.
+fn check_against_cursor(reader: &mut Reader, expected: &[u8], ops: &[Op]) -> Result<(), TestCaseError> {
+    let mut cursor = Cursor::new(expected);
+
+    for op in ops {
+        match op {
+            Op::Seek(destination) => {
+                let actual = reader.seek(*destination).ok();
+                let model = cursor.seek(*destination).ok();
+                prop_assert_eq!(actual, model, "seek {:?}", destination);
+            },
+            Op::Read(count) => {
+                let mut actual = vec![0; *count];
+                let mut model = vec![0; *count];
+                let actual_count = reader.read(&mut actual).unwrap();
+                let model_count = cursor.read(&mut model).unwrap();
+                prop_assert_eq!(&actual[..actual_count], &model[..model_count]);
+            },
+        }
+    }
+
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn limit_reads_the_slice((data, offset, length) in data_and_slice()) {
+        let reader = Reader::from_bytes(&data);
+        let mut slice = reader.limit(offset, length).unwrap();
+
+        let mut buffer = vec![];
+        slice.read_to_end(&mut buffer).unwrap();
+        prop_assert_eq!(&buffer[..], &data[offset as usize..(offset + length) as usize]);
+        prop_assert_eq!(Reader::bytes(&slice), Some(&data[offset as usize..(offset + length) as usize]));
+    }
+
+    #[test]
+    fn limit_rejects_out_of_bounds(data in prop::collection::vec(any::<u8>(), 0..64), offset in any::<u64>(), length in any::<u64>()) {
+        let reader = Reader::from_bytes(&data);
+        let in_bounds = offset.checked_add(length).is_some_and(|end| end <= data.len() as u64);
+
+        prop_assert_eq!(reader.limit(offset, length).is_ok(), in_bounds);
+    }
+
+    #[test]
+    fn nested_limits_compose((data, offset, length) in data_and_slice(), inner in any::<(u64, u64)>()) {
+        let outer = Reader::from_bytes(&data).limit(offset, length).unwrap();
+        let (inner_offset, inner_length) = (inner.0 % (length + 1), inner.1 % (length + 1));
+
+        match outer.limit(inner_offset, inner_length) {
+            Ok(mut reader) => {
+                let start = (offset + inner_offset) as usize;
+                let mut buffer = vec![];
+                reader.read_to_end(&mut buffer).unwrap();
+                prop_assert_eq!(&buffer[..], &data[start..start + inner_length as usize]);
+            },
+            Err(_) => prop_assert!(inner_offset + inner_length > length),
+        }
+    }
+
+    #[test]
+    fn seeks_and_reads_match_cursor((data, offset, length) in data_and_slice(), ops in prop::collection::vec(op(), 0..32)) {
+        let expected = &data[offset as usize..(offset + length) as usize];
+
+        let mut reader = Reader::from_bytes(&data).limit(offset, length).unwrap();
+        check_against_cursor(&mut reader, expected, &ops)?;
+
+        // same thing through a Source instead of a byte slice.
+        let holder = FileHolder::from_stream(Cursor::new(data.clone())).unwrap();
+        let mut reader = holder.reader().limit(offset, length).unwrap();
+        check_against_cursor(&mut reader, expected, &ops)?;
+    }
+
+    #[test]
+    fn read_exact_at_leaves_position_alone((data, offset, length) in data_and_slice(), at in 0u64..600, count in 0usize..64) {
+        let mut reader = Reader::from_bytes(&data).limit(offset, length).unwrap();
+        reader.seek(SeekFrom::Start(1)).unwrap();
+
+        let mut buffer = vec![0; count];
+        match reader.read_exact_at(at, &mut buffer) {
+            Ok(()) => {
+                let start = (offset + at) as usize;
+                prop_assert_eq!(&buffer[..], &data[start..start + count]);
+            },
+            Err(_) => prop_assert!(at + count as u64 > length),
+        }
+
+        prop_assert_eq!(reader.stream_position().unwrap(), 1);
+    }
+
+    #[test]
+    fn owned_readers_match_borrowed((data, offset, length) in data_and_slice()) {
+        let borrowed = Reader::from_bytes(&data).limit(offset, length).unwrap();
+        let owned = FileHolder::from_bytes(data.clone()).into_shared().limit(offset, length).unwrap();
+
+        prop_assert_eq!(Reader::bytes(&borrowed), Reader::bytes(&owned));
+    }
+}
+
+#[test]
+fn seeking_to_the_end_is_allowed() {
+    let data = [1, 2, 3, 4];
+    let mut reader = Reader::from_bytes(&data);
+
+    assert_eq!(reader.seek(SeekFrom::Start(4)).unwrap(), 4);
+    assert_eq!(reader.seek(SeekFrom::End(0)).unwrap(), 4);
+    assert_eq!(reader.seek(SeekFrom::Start(0)).unwrap(), 0);
+    assert_eq!(reader.seek(SeekFrom::Current(4)).unwrap(), 4);
+    assert_eq!(reader.read(&mut [0; 4]).unwrap(), 0);
+}
+
+#[test]
+fn reads_past_the_end_return_nothing() {
+    let data = [1, 2, 3, 4];
+    let mut reader = Reader::from_bytes(&data).limit(1, 2).unwrap();
+
+    assert_eq!(reader.seek(SeekFrom::End(10)).unwrap(), 12);
+    assert_eq!(reader.read(&mut [0; 4]).unwrap(), 0);
+    assert!(reader.seek(SeekFrom::Current(-13)).is_err());
+    assert_eq!(reader.seek(SeekFrom::Current(-11)).unwrap(), 1);
+
+    let mut buffer = [0; 4];
+    assert_eq!(reader.read(&mut buffer).unwrap(), 1);
+    assert_eq!(buffer[0], 3);
+}
+
+#[test]
+fn hostile_offsets_dont_overflow() {
+    let data = [0; 16];
+    let reader = Reader::from_bytes(&data);
+
+    assert!(reader.limit(u64::MAX, 1).is_err());
+    assert!(reader.limit(1, u64::MAX).is_err());
+    assert!(reader.at(u64::MAX).is_err());
+    assert!(reader.at(16).is_ok());
+    assert!(reader.read_exact_at(u64::MAX, &mut [0; 2]).is_err());
+}
@@ -0,0 +1,285 @@
+use std::io::Read;
+use vgc_data::extract;
+use vgc_data::read::Reader;
+use vgc_data::read::VirtualFile;
+use vgc_data::romfs::Node;
+use vgc_data::romfs::RomFS;
+
+const NONE: u32 = 0xFFFF_FFFF;
+
+// (name, parent index). The root is first.
+const DIRECTORIES: [(&str, usize); 4] = [("", 0), ("a", 0), ("b", 1), ("c", 0)];
+// (name, parent index, contents)
+const FILES: [(&str, usize, &[u8]); 3] = [("x", 0, b"hello"), ("y", 2, b"world!"), ("a", 3, b"file-a")];
+
+fn hash(parent: u32, name: &str) -> u32 {
+    name.encode_utf16().fold(parent ^ 123456789, |hash, unit| hash.rotate_right(5) ^ unit as u32)
+}
+
+fn name(name: &str) -> Vec<u8> {
+    let mut bytes = name.encode_utf16().flat_map(|unit| unit.to_le_bytes().to_vec()).collect::<Vec<_>>();
+    bytes.resize(bytes.len().div_ceil(4) * 4, 0);
+    bytes
+}
+
+fn u32s(values: &[u32]) -> Vec<u8> {
+    values.iter().flat_map(|value| value.to_le_bytes().to_vec()).collect()
+}
+
+struct Image {
+    data: Vec<u8>,
+    // Where the level 3 sections start, from the start of the image.
+    directory_hash_table: usize,
+    directory_metadata: usize,
+    file_hash_table: usize,
+    file_metadata: usize,
+}
+
+// A RomFS with the tree above, and `buckets` buckets in each hash table.
+fn build(buckets: usize) -> Image {
+    let mut directory_offsets = vec![];
+    let mut offset = 0;
+    for (directory, _) in &DIRECTORIES {
+        directory_offsets.push(offset);
+        offset += 0x18 + name(directory).len() as u32;
+    }
+
+    let mut file_offsets = vec![];
+    let mut offset = 0;
+    for (file, _, _) in &FILES {
+        file_offsets.push(offset);
+        offset += 0x20 + name(file).len() as u32;
+    }
+
+    let mut directory_buckets = vec![NONE; buckets];
+    let mut file_buckets = vec![NONE; buckets];
+    let mut directory_hash_next = vec![NONE; DIRECTORIES.len()];
+    let mut file_hash_next = vec![NONE; FILES.len()];
+    if buckets > 0 {
+        for (i, (directory, parent)) in DIRECTORIES.iter().enumerate() {
+            let bucket = (hash(directory_offsets[*parent], directory) % buckets as u32) as usize;
+            directory_hash_next[i] = directory_buckets[bucket];
+            directory_buckets[bucket] = directory_offsets[i];
+        }
+        for (i, (file, parent, _)) in FILES.iter().enumerate() {
+            let bucket = (hash(directory_offsets[*parent], file) % buckets as u32) as usize;
+            file_hash_next[i] = file_buckets[bucket];
+            file_buckets[bucket] = file_offsets[i];
+        }
+    }
+
+    let mut directories = vec![];
+    for (i, (directory, parent)) in DIRECTORIES.iter().enumerate() {
+        let sibling = (i + 1..DIRECTORIES.len()).find(|&j| i != 0 && DIRECTORIES[j].1 == *parent);
+        let subdirectory = (1..DIRECTORIES.len()).find(|&j| DIRECTORIES[j].1 == i);
+        let file = (0..FILES.len()).find(|&j| FILES[j].1 == i);
+
+        directories.extend(u32s(&[
+            directory_offsets[*parent],
+            sibling.map_or(NONE, |j| directory_offsets[j]),
+            subdirectory.map_or(NONE, |j| directory_offsets[j]),
+            file.map_or(NONE, |j| file_offsets[j]),
+            directory_hash_next[i],
+            2 * directory.len() as u32,
+        ]));
+        directories.extend(name(directory));
+    }
+
+    let mut files = vec![];
+    let mut contents: Vec<u8> = vec![];
+    for (i, (file, parent, data)) in FILES.iter().enumerate() {
+        let sibling = (i + 1..FILES.len()).find(|&j| FILES[j].1 == *parent);
+
+        files.extend(u32s(&[directory_offsets[*parent], sibling.map_or(NONE, |j| file_offsets[j])]));
+        files.extend(&(contents.len() as u64).to_le_bytes());
+        files.extend(&(data.len() as u64).to_le_bytes());
+        files.extend(u32s(&[file_hash_next[i], 2 * file.len() as u32]));
+        files.extend(name(file));
+        contents.extend(*data);
+    }
+
+    let sections = [u32s(&directory_buckets), directories, u32s(&file_buckets), files];
+    let mut lvl3 = u32s(&[0x28]);
+    let mut offset = 0x28;
+    let mut starts = vec![];
+    for section in &sections {
+        starts.push(0x60 + offset as usize);
+        lvl3.extend(u32s(&[offset, section.len() as u32]));
+        offset += section.len() as u32;
+    }
+    lvl3.extend(u32s(&[offset]));
+    for section in &sections {
+        lvl3.extend(section);
+    }
+    lvl3.extend(contents);
+
+    // IVFC header: level 3 starts right after it, at 0x60.
+    let mut data = b"IVFC".to_vec();
+    data.extend(u32s(&[0x10000, 0]));
+    for _ in 0..3 {
+        data.extend(&[0; 16]);
+        data.extend(u32s(&[4, 0]));
+    }
+    data.extend(u32s(&[0x5C, 0]));
+    data.resize(0x60, 0);
+    data.extend(lvl3);
+
+    Image { data, directory_hash_table: starts[0], directory_metadata: starts[1], file_hash_table: starts[2], file_metadata: starts[3] }
+}
+
+fn describe(romfs: &RomFS, path: &str) -> String {
+    match romfs.file_at(path).unwrap() {
+        Some(Node::File(file)) => {
+            let mut contents = String::new();
+            file.reader().unwrap().read_to_string(&mut contents).unwrap();
+            format!("file {}", contents)
+        },
+        Some(Node::Directory(dir)) => format!("dir {}", dir.basename()),
+        None => "-".to_string(),
+    }
+}
+
+fn check_lookups(data: &[u8]) {
+    let romfs = RomFS::new(Reader::from_bytes(data)).unwrap();
+
+    assert_eq!(describe(&romfs, ""), "dir ");
+    assert_eq!(describe(&romfs, "x"), "file hello");
+    assert_eq!(describe(&romfs, "a/b/y"), "file world!");
+    assert_eq!(describe(&romfs, "/a/b/"), "dir b");
+    assert_eq!(describe(&romfs, "c/a"), "file file-a");
+    assert_eq!(describe(&romfs, "a/x"), "-");
+    assert_eq!(describe(&romfs, "x/y"), "-");
+
+    assert!(romfs.exists("c").unwrap());
+    assert!(!romfs.exists("nope").unwrap());
+    assert!(romfs.dir_at("a/b").unwrap().is_some());
+    assert!(romfs.dir_at("a/b/y").unwrap().is_none());
+}
+
+#[test]
+fn hashed_lookups() {
+    check_lookups(&build(3).data);
+    check_lookups(&build(1).data);
+}
+
+#[test]
+fn lookups_without_hash_tables() {
+    check_lookups(&build(0).data);
+}
+
+#[test]
+fn stale_hash_tables_fall_back_to_the_sibling_lists() {
+    let mut image = build(3);
+    let empty = u32s(&[NONE; 3]);
+    image.data[image.directory_hash_table..image.directory_hash_table + 12].copy_from_slice(&empty);
+    image.data[image.file_hash_table..image.file_hash_table + 12].copy_from_slice(&empty);
+
+    check_lookups(&image.data);
+}
+
+#[test]
+fn hash_chain_loops_are_errors() {
+    let mut image = build(1);
+    // Every file in the single bucket, and the first one pointing to itself.
+    let first = &mut image.data[image.file_metadata..];
+    first[0x18..0x1C].copy_from_slice(&0u32.to_le_bytes());
+    image.data[image.file_hash_table..image.file_hash_table + 4].copy_from_slice(&0u32.to_le_bytes());
+
+    let romfs = RomFS::new(Reader::from_bytes(&image.data)).unwrap();
+    assert!(romfs.file_at("nope").is_err());
+}
+
+#[test]
+fn sibling_list_loops_are_errors() {
+    for buckets in &[0, 3] {
+        let mut image = build(*buckets);
+        // The root's only file, x, is its own next sibling.
+        image.data[image.file_metadata + 4..image.file_metadata + 8].copy_from_slice(&0u32.to_le_bytes());
+
+        let romfs = RomFS::new(Reader::from_bytes(&image.data)).unwrap();
+        assert!(romfs.exists("nope").is_err());
+    }
+}
+
+#[test]
+fn walk_is_depth_first() {
+    let image = build(3);
+    let romfs = RomFS::new(Reader::from_bytes(&image.data)).unwrap();
+
+    let paths = romfs.walk().unwrap().map(|entry| entry.unwrap().0).collect::<Vec<_>>();
+    assert_eq!(paths, ["a", "a/b", "a/b/y", "c", "c/a", "x"]);
+
+    for entry in romfs.walk().unwrap() {
+        let (path, node) = entry.unwrap();
+        assert_eq!(node.path().unwrap(), path);
+    }
+}
+
+#[test]
+fn walking_into_an_ancestor_is_an_error() {
+    let mut image = build(3);
+    // a is at 0x18 and a/b at 0x34. Make a the first subdirectory of a/b.
+    let b = image.directory_metadata + 0x34;
+    image.data[b + 8..b + 12].copy_from_slice(&0x18u32.to_le_bytes());
+
+    let romfs = RomFS::new(Reader::from_bytes(&image.data)).unwrap();
+    let entries = romfs.walk().unwrap().collect::<Vec<_>>();
+    assert!(entries.last().unwrap().is_err());
+    assert!(entries.len() < 10);
+}
+
+#[test]
+fn extraction_stays_inside_the_destination() {
+    let destination = std::env::temp_dir().join(format!("vgc-data-romfs-{}", std::process::id()));
+
+    for bad in &["..", "/", "\\"] {
+        // x is the first file entry; its name has room for two units.
+        let mut image = build(3);
+        let x = image.file_metadata;
+        let units = bad.encode_utf16().flat_map(|unit| unit.to_le_bytes().to_vec()).collect::<Vec<_>>();
+        image.data[x + 0x1C..x + 0x20].copy_from_slice(&(units.len() as u32).to_le_bytes());
+        image.data[x + 0x20..x + 0x24].fill(0);
+        image.data[x + 0x20..x + 0x20 + units.len()].copy_from_slice(&units);
+
+        let romfs = RomFS::new(Reader::from_bytes(&image.data)).unwrap();
+        assert!(extract::romfs_jobs(&romfs, &destination).is_err(), "{:?}", bad);
+    }
+
+    let image = build(3);
+    let romfs = RomFS::new(Reader::from_bytes(&image.data)).unwrap();
+    assert_eq!(extract::romfs_jobs(&romfs, &destination).unwrap().len(), FILES.len());
+
+    std::fs::remove_dir_all(&destination).unwrap();
+}
+
+#[test]
+fn glob_matches_within_components() {
+    let image = build(3);
+    let romfs = RomFS::new(Reader::from_bytes(&image.data)).unwrap();
+    let glob = |pattern| romfs.glob(pattern).unwrap().into_iter().map(|(path, _)| path).collect::<Vec<_>>();
+
+    assert_eq!(glob("*"), ["a", "c", "x"]);
+    assert_eq!(glob("*/a"), ["c/a"]);
+    assert_eq!(glob("?/b/*"), ["a/b/y"]);
+    assert_eq!(glob("*/*/*"), ["a/b/y"]);
+    assert_eq!(glob("x*"), ["x"]);
+    assert!(glob("x/*").is_empty());
+}
+
+#[test]
+fn parents_and_sizes() {
+    let image = build(3);
+    let romfs = RomFS::new(Reader::from_bytes(&image.data)).unwrap();
+
+    let file = match romfs.file_at("a/b/y").unwrap() {
+        Some(Node::File(file)) => file,
+        other => panic!("expected a file, got {:?}", other),
+    };
+    assert_eq!(file.size(), 6);
+    assert_eq!(file.path().unwrap(), "a/b/y");
+
+    let parent = file.parent().unwrap();
+    assert_eq!(parent.path().unwrap(), "a/b");
+    assert_eq!(parent.parent().unwrap().unwrap().path().unwrap(), "a");
+    assert!(romfs.root().unwrap().parent().unwrap().is_none());
+}
@@ -0,0 +1,98 @@
+use proptest::prelude::*;
+use vgc_data::pokemon::encounters::Zone;
+use vgc_data::pokemon::static_encounters::Gift;
+use vgc_data::pokemon::static_encounters::StaticEncounter;
+use vgc_data::pokemon::static_encounters::Trade;
+use vgc_data::pokemon::table::write_records;
+use vgc_data::pokemon::table::Record;
+use vgc_data::pokemon::table::Table;
+use vgc_data::pokemon::table::TableBuilder;
+use vgc_data::read::Reader;
+
+#[test]
+fn odd_lengths_round_trip() {
+    let mut builder = TableBuilder::new();
+    builder.push_records(&[1u8, 2, 3]).unwrap();
+    builder.push_records(&[7u16, 8, 9]).unwrap();
+    builder.push_records(&[0xDEADBEEFu32]).unwrap();
+    builder.push(vec![]);
+    let data = builder.build().unwrap();
+
+    let table = Table::new(Reader::from_bytes(&data)).unwrap();
+    assert_eq!(table.magic(), *b"BL");
+    assert_eq!(table.len(), 4);
+    assert_eq!(table.u8s(0).unwrap(), [1, 2, 3]);
+    assert_eq!(table.u16s(1).unwrap(), [7, 8, 9]);
+    assert_eq!(table.u32s(2).unwrap(), [0xDEADBEEF]);
+    assert_eq!(table.u8s(3).unwrap(), []);
+}
+
+#[test]
+fn aligned_tables_keep_their_padding() {
+    let mut builder = TableBuilder::with_magic(*b"WD");
+    builder.align(4);
+    builder.push_records(&[1u8, 2, 3]).unwrap();
+    builder.push_records(&[7u16, 8, 9]).unwrap();
+    let data = builder.build().unwrap();
+
+    let table = Table::new(Reader::from_bytes(&data)).unwrap();
+    assert_eq!(table.magic(), *b"WD");
+    assert_eq!(table.u8s(0).unwrap(), [1, 2, 3, 0]);
+    assert_eq!(table.u16s(1).unwrap(), [7, 8, 9, 0]);
+}
+
+proptest! {
+    #[test]
+    fn builder_round_trips(tables in prop::collection::vec(prop::collection::vec(any::<u16>(), 0..32), 0..16)) {
+        let mut builder = TableBuilder::new();
+        for records in &tables {
+            builder.push_records(records).unwrap();
+        }
+        let data = builder.build().unwrap();
+
+        let table = Table::new(Reader::from_bytes(&data)).unwrap();
+        prop_assert_eq!(table.len() as usize, tables.len());
+        for (i, records) in tables.iter().enumerate() {
+            prop_assert_eq!(&table.u16s(i).unwrap(), records);
+        }
+    }
+}
+
+#[test]
+fn too_many_tables_are_errors() {
+    let mut builder = TableBuilder::new();
+    for _ in 0..=u16::MAX as usize {
+        builder.push(vec![]);
+    }
+
+    assert!(builder.build().is_err());
+}
+
+#[test]
+fn default_zones_are_whole_records() {
+    let zone = Zone::default();
+    assert_eq!(zone.location_name_index(), 0);
+
+    let mut data = vec![];
+    write_records(&[zone], &mut data).unwrap();
+    assert_eq!(data.len(), Zone::SIZE);
+}
+
+#[test]
+fn default_static_records_are_whole_records() {
+    let mut gift = Gift::default();
+    gift.species = 25;
+
+    let mut data = vec![];
+    write_records(&[gift], &mut data).unwrap();
+    assert_eq!(data.len(), Gift::SIZE);
+    assert_eq!(&data[0..2], &25u16.to_le_bytes());
+
+    let mut data = vec![];
+    write_records(&[StaticEncounter::default()], &mut data).unwrap();
+    assert_eq!(data.len(), StaticEncounter::SIZE);
+
+    let mut data = vec![];
+    write_records(&[Trade::default()], &mut data).unwrap();
+    assert_eq!(data.len(), Trade::SIZE);
+}
@@ -0,0 +1,120 @@
+use proptest::prelude::*;
+use vgc_data::pokemon::text::parse;
+use vgc_data::pokemon::text::render;
+use vgc_data::pokemon::text::TextLine;
+use vgc_data::pokemon::text::TextToken;
+use vgc_data::pokemon::text::Texts;
+use vgc_data::pokemon::text::TextsBuilder;
+use vgc_data::read::Reader;
+
+const LINES: [&str; 4] = [
+    "Hello, [VAR 0100(0000)]!",
+    "First line\\nsecond line\\rthird[WAIT 30]\\c",
+    "",
+    "Brackets \\[ and backslashes \\\\ [~ 12][CHAR D800][VAR BD00]",
+];
+
+#[test]
+fn multiple_sections_round_trip() {
+    let mut builder = TextsBuilder::new();
+    for line in &LINES {
+        builder.push(line).unwrap();
+    }
+    builder.push_section();
+    for (i, line) in LINES.iter().enumerate() {
+        builder.push_line(&TextLine { text: line.to_string(), flags: i as u16 }).unwrap();
+    }
+    let data = builder.build().unwrap();
+
+    let texts = Texts::new(Reader::from_bytes(&data)).unwrap();
+    assert_eq!(texts.section_count(), 2);
+    assert_eq!(texts.line_count(), LINES.len() as u16);
+
+    let first = texts.section(0).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(first, LINES);
+
+    let second = texts.section(1).unwrap().lines().collect::<Result<Vec<_>, _>>().unwrap();
+    for (i, line) in second.iter().enumerate() {
+        assert_eq!(line, &TextLine { text: LINES[i].to_string(), flags: i as u16 });
+    }
+}
+
+#[test]
+fn sections_need_the_same_line_count() {
+    let mut builder = TextsBuilder::new();
+    builder.push("one").unwrap();
+    builder.push_section();
+
+    assert!(builder.build().is_err());
+}
+
+#[test]
+fn unterminated_commands_are_errors() {
+    assert!(parse("[WAIT 5").is_err());
+    assert!(parse("text [VAR 0100(0000)").is_err());
+    assert_eq!(parse("[WAIT 5]").unwrap(), [TextToken::Wait(5)]);
+}
+
+#[test]
+fn terminators_are_rejected() {
+    let mut builder = TextsBuilder::new();
+
+    assert!(builder.push("cut\u{0}short").is_err());
+    assert!(builder.push("[CHAR 0000]").is_err());
+    assert!(builder.push_tokens(&[TextToken::Text("a\u{0}b".to_string())], 0).is_err());
+}
+
+#[test]
+fn variable_markers_are_rejected() {
+    let mut builder = TextsBuilder::new();
+
+    assert!(builder.push("stray\u{10}marker").is_err());
+    assert!(builder.push("[CHAR 0010]").is_err());
+    assert!(builder.push_tokens(&[TextToken::Raw(0x10)], 0).is_err());
+    assert!(builder.push("[VAR 0100]").is_ok());
+}
+
+// Without terminators. 0x10 starts a variable, so text containing it has to
+// be rejected rather than read back as something else.
+const PLAIN_TEXT: &str = "[^\u{0}]*";
+
+proptest! {
+    #[test]
+    fn plain_text_round_trips(lines in prop::collection::vec(PLAIN_TEXT, 1..8)) {
+        let mut builder = TextsBuilder::new();
+        let mut pushed = vec![];
+        for line in &lines {
+            let result = builder.push_tokens(&[TextToken::Text(line.clone())], 0);
+            prop_assert_eq!(result.is_err(), line.contains('\u{10}'));
+            if result.is_ok() {
+                pushed.push(line);
+            }
+        }
+        let data = builder.build().unwrap();
+
+        let texts = Texts::new(Reader::from_bytes(&data)).unwrap();
+        let read = texts.tokens().collect::<Result<Vec<_>, _>>().unwrap();
+        prop_assert_eq!(read.len(), pushed.len());
+        for (line, tokens) in pushed.into_iter().zip(read) {
+            prop_assert_eq!(render(&tokens), render(&[TextToken::Text(line.clone())]));
+        }
+    }
+
+    #[test]
+    fn markup_round_trips(tokens in prop::collection::vec(token(), 0..8)) {
+        let markup = render(&tokens);
+        prop_assert_eq!(render(&parse(&markup).unwrap()), markup);
+    }
+}
+
+fn token() -> impl Strategy<Value = TextToken> {
+    prop_oneof![
+        "[^\u{0}]{1,8}".prop_map(TextToken::Text),
+        Just(TextToken::Return),
+        Just(TextToken::Clear),
+        any::<u16>().prop_map(TextToken::Wait),
+        any::<u16>().prop_map(TextToken::Null),
+        (any::<u16>(), prop::collection::vec(any::<u16>(), 0..4)).prop_map(|(code, args)| TextToken::Variable { code, args }),
+        (1u16..).prop_map(TextToken::Raw),
+    ]
+}